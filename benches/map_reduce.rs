@@ -0,0 +1,165 @@
+use std::{env, fs, path::PathBuf, sync::Arc};
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion, Throughput};
+use parking_lot::Mutex;
+use rs_map_reduce::{
+    map_sequential, run_map_reduce_with_pool, Combine, FileInputData, GenericInputData,
+    MmapFileInputData, Reducer, RunOptions, StringInputData, ThreadPool, WordCountWorker,
+};
+
+const SIZES: [usize; 3] = [10, 100, 1000];
+const BUFFER_SIZES: [usize; 3] = [8 * 1024, 64 * 1024, 1024 * 1024];
+
+fn generate_workers(count: usize) -> Vec<Arc<Mutex<WordCountWorker>>> {
+    (0..count)
+        .map(|index| {
+            let content = format!("line {index} with a few words\n").repeat(200);
+            let input_data = Arc::new(StringInputData::new(content));
+            Arc::new(Mutex::new(WordCountWorker::new(input_data)))
+        })
+        .collect()
+}
+
+fn run_sequential(workers: Vec<Arc<Mutex<WordCountWorker>>>) -> usize {
+    for outcome in map_sequential(&workers, None) {
+        outcome.expect("Failed to map worker");
+    }
+    workers
+        .iter()
+        .fold(WordCountWorker::identity(), |mut reduced, worker| {
+            reduced.reduce(&worker.lock());
+            reduced
+        })
+        .get_result()
+}
+
+fn map_reduce(c: &mut Criterion) {
+    let pool = ThreadPool::default();
+    let mut group = c.benchmark_group("map_reduce");
+
+    for size in SIZES {
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::new("sequential", size), &size, |b, &size| {
+            b.iter_batched(
+                || generate_workers(size),
+                run_sequential,
+                BatchSize::SmallInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("thread_pool", size), &size, |b, &size| {
+            b.iter_batched(
+                || generate_workers(size),
+                |workers| {
+                    run_map_reduce_with_pool(&pool, workers, RunOptions::new())
+                        .expect("Failed to run map-reduce")
+                        .result
+                },
+                BatchSize::SmallInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+fn mapped_workers(count: usize) -> Vec<Arc<Mutex<WordCountWorker>>> {
+    let workers = generate_workers(count);
+    for outcome in map_sequential(&workers, None) {
+        outcome.expect("Failed to map worker");
+    }
+    workers
+}
+
+// Compares folding mapped workers through their locks, as the reduce used to,
+// with taking them out of their mutexes and combining them as owned values.
+fn reduce(c: &mut Criterion) {
+    let mut group = c.benchmark_group("reduce");
+
+    for size in SIZES {
+        group.throughput(Throughput::Elements(size as u64));
+        group.bench_with_input(BenchmarkId::new("locked", size), &size, |b, &size| {
+            b.iter_batched(
+                || mapped_workers(size),
+                |workers| {
+                    workers
+                        .iter()
+                        .fold(WordCountWorker::identity(), |mut reduced, worker| {
+                            reduced.reduce(&worker.lock());
+                            reduced
+                        })
+                        .get_result()
+                },
+                BatchSize::SmallInput,
+            )
+        });
+        group.bench_with_input(BenchmarkId::new("owned", size), &size, |b, &size| {
+            b.iter_batched(
+                || mapped_workers(size),
+                |workers| {
+                    workers
+                        .into_iter()
+                        .map(|worker| {
+                            Arc::try_unwrap(worker)
+                                .unwrap_or_else(|_| panic!("Worker is still shared"))
+                                .into_inner()
+                        })
+                        .fold(WordCountWorker::identity(), WordCountWorker::combine)
+                        .get_result()
+                },
+                BatchSize::SmallInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+fn buffer_size(c: &mut Criterion) {
+    let file_path: PathBuf = env::temp_dir().join("rs_map_reduce_buffer_size.txt");
+    fs::write(&file_path, "line with a few words\n".repeat(1_000_000))
+        .expect("Failed to write bench input");
+    let mut group = c.benchmark_group("buffer_size");
+
+    for buffer_size in BUFFER_SIZES {
+        let input_data = FileInputData::with_buffer_size(file_path.clone(), buffer_size);
+        group.bench_with_input(
+            BenchmarkId::from_parameter(buffer_size),
+            &input_data,
+            |b, input_data| {
+                b.iter(|| {
+                    GenericInputData::<String>::count_lines(input_data)
+                        .expect("Failed to read bench input")
+                })
+            },
+        );
+    }
+
+    group.finish();
+    fs::remove_file(&file_path).expect("Failed to remove bench input");
+}
+
+fn count_lines(c: &mut Criterion) {
+    let file_path: PathBuf = env::temp_dir().join("rs_map_reduce_count_lines.txt");
+    fs::write(&file_path, "line with a few words\n".repeat(1_000_000))
+        .expect("Failed to write bench input");
+    let mut group = c.benchmark_group("count_lines");
+
+    let buffered = FileInputData::new(file_path.clone());
+    group.bench_function("buffered", |b| {
+        b.iter(|| {
+            GenericInputData::<String>::count_lines(&buffered).expect("Failed to read bench input")
+        })
+    });
+    let mmap = MmapFileInputData::new(file_path.clone());
+    group.bench_function("mmap", |b| {
+        b.iter(|| {
+            GenericInputData::<String>::count_lines(&mmap).expect("Failed to read bench input")
+        })
+    });
+
+    group.finish();
+    fs::remove_file(&file_path).expect("Failed to remove bench input");
+}
+
+criterion_group!(benches, map_reduce, reduce, buffer_size, count_lines);
+criterion_main!(benches);
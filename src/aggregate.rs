@@ -0,0 +1,66 @@
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+
+use crate::{map_all_with, MapReduceError, MapReducer, RunOptions, ThreadPool};
+
+/// Collects mapped results into a separate accumulator, so the reduce target
+/// is never one of the workers that was mapped.
+pub trait Aggregator {
+    type Item;
+    type Output;
+
+    fn absorb(&mut self, item: Self::Item);
+    fn finish(self) -> Self::Output;
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LineCountAggregator {
+    total: usize,
+}
+
+impl Aggregator for LineCountAggregator {
+    type Item = usize;
+    type Output = usize;
+
+    fn absorb(&mut self, item: Self::Item) {
+        self.total += item;
+    }
+
+    fn finish(self) -> Self::Output {
+        self.total
+    }
+}
+
+pub fn aggregate_with<W, A>(
+    workers: Vec<Arc<Mutex<W>>>,
+    mut aggregator: A,
+) -> Result<A::Output, MapReduceError>
+where
+    W: MapReducer + 'static,
+    A: Aggregator<Item = W::Output>,
+{
+    let mapped = map_all_with(&ThreadPool::default(), workers, RunOptions::new())?;
+    for worker in &mapped.workers {
+        aggregator.absorb(worker.get_result());
+    }
+    Ok(aggregator.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{create_workers, test_util::string_inputs, LineCountWorker};
+
+    #[test]
+    fn every_input_is_absorbed_exactly_once() {
+        let workers = create_workers(
+            string_inputs(&["one", "two\nthree", "four\nfive\nsix"]),
+            LineCountWorker::new,
+        );
+        assert_eq!(
+            aggregate_with(workers, LineCountAggregator::default()).unwrap(),
+            6
+        );
+    }
+}
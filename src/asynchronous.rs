@@ -0,0 +1,132 @@
+use std::{
+    future::Future,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use futures::future::join_all;
+
+use crate::{
+    input::decode_file_contents, pool::catch_panics, GenericInputData, MapReduceError, MapReducer,
+    StringInputData,
+};
+
+pub trait AsyncGenericInputData<T>: Send + Sync {
+    fn read(&self) -> impl Future<Output = Result<T, MapReduceError>> + Send;
+}
+
+pub struct AsyncFileInputData {
+    file_path: PathBuf,
+}
+
+impl AsyncFileInputData {
+    pub fn new(file_path: PathBuf) -> Self {
+        Self { file_path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.file_path
+    }
+}
+
+impl<T> AsyncGenericInputData<T> for AsyncFileInputData
+where
+    T: From<String>,
+{
+    async fn read(&self) -> Result<T, MapReduceError> {
+        let bytes = tokio::fs::read(&self.file_path).await?;
+        Ok(T::from(decode_file_contents(&self.file_path, bytes)?))
+    }
+}
+
+pub async fn run_map_reduce_async<I, W, F>(
+    inputs: Vec<I>,
+    make_worker: F,
+) -> Result<W::Output, MapReduceError>
+where
+    I: AsyncGenericInputData<String> + 'static,
+    W: MapReducer + 'static,
+    F: Fn(Arc<dyn GenericInputData<String>>) -> W + Send + Sync + 'static,
+{
+    let make_worker = Arc::new(make_worker);
+
+    // Reading is the only part that awaits; once the content is in memory the
+    // worker maps it synchronously inside its task.
+    let tasks = inputs.into_iter().map(|input| {
+        let make_worker = Arc::clone(&make_worker);
+        tokio::task::spawn(async move {
+            let content = input.read().await?;
+            let mut worker = make_worker(Arc::new(StringInputData::new(content)));
+            catch_panics(|| worker.map())?;
+            Ok::<_, MapReduceError>(worker)
+        })
+    });
+
+    let mut first_error = None;
+    let mut mapped = Vec::new();
+    for outcome in join_all(tasks).await {
+        match outcome.expect("Async map task panicked") {
+            Ok(worker) => mapped.push(worker),
+            Err(error) => {
+                first_error.get_or_insert(error);
+            }
+        }
+    }
+
+    if let Some(error) = first_error.filter(|_| mapped.is_empty()) {
+        return Err(error);
+    }
+
+    let aggregate = mapped.into_iter().fold(W::identity(), W::combine);
+    Ok(aggregate.get_result())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        generate_inputs, run,
+        test_util::{PanickingInput, TempDir},
+        LineCountWorker,
+    };
+
+    #[tokio::test]
+    async fn async_run_matches_the_sync_run() {
+        let dir = TempDir::new();
+        dir.write("a.txt", "one\ntwo\n");
+        dir.write("b.txt", "three\nfour\nfive");
+        dir.write("c.txt", "");
+
+        let sync_total = run(generate_inputs(dir.path()).unwrap(), LineCountWorker::new).unwrap();
+        let inputs: Vec<AsyncFileInputData> = ["a.txt", "b.txt", "c.txt"]
+            .iter()
+            .map(|name| AsyncFileInputData::new(dir.path().join(name)))
+            .collect();
+        let async_total = run_map_reduce_async(inputs, LineCountWorker::new)
+            .await
+            .unwrap();
+
+        assert_eq!(async_total, sync_total);
+        assert_eq!(async_total, 5);
+    }
+
+    #[tokio::test]
+    async fn a_panicking_map_is_skipped() {
+        let dir = TempDir::new();
+        let inputs: Vec<AsyncFileInputData> = [("a.txt", "one\ntwo"), ("b.txt", "boom")]
+            .iter()
+            .map(|(name, content)| AsyncFileInputData::new(dir.write(name, content)))
+            .collect();
+
+        let total = run_map_reduce_async(inputs, |input: Arc<dyn GenericInputData<String>>| {
+            // Swapping in an input that panics makes this worker's map panic.
+            let input: Arc<dyn GenericInputData<String>> = match input.read() {
+                Ok(content) if content == "boom" => Arc::new(PanickingInput),
+                _ => input,
+            };
+            LineCountWorker::new(input)
+        })
+        .await;
+        assert_eq!(total.unwrap(), 2);
+    }
+}
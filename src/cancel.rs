@@ -0,0 +1,160 @@
+use std::{
+    any::Any,
+    fmt,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+impl From<Arc<AtomicBool>> for CancellationToken {
+    fn from(flag: Arc<AtomicBool>) -> Self {
+        Self(flag)
+    }
+}
+
+// The error type is not generic over worker outputs, so the result reduced
+// before cancellation is carried type-erased and recovered with `downcast`.
+pub struct PartialResult {
+    result: Box<dyn Any + Send + Sync>,
+    inputs_processed: usize,
+}
+
+impl PartialResult {
+    pub(crate) fn new<T: Send + Sync + 'static>(result: T, inputs_processed: usize) -> Self {
+        Self {
+            result: Box::new(result),
+            inputs_processed,
+        }
+    }
+
+    pub fn inputs_processed(&self) -> usize {
+        self.inputs_processed
+    }
+
+    /// Returns the result if it is a `T`, i.e. the `Output` of the worker the
+    /// run was started with.
+    pub fn downcast<T: 'static>(self) -> Option<T> {
+        self.result.downcast().ok().map(|result| *result)
+    }
+}
+
+impl fmt::Debug for PartialResult {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PartialResult")
+            .field("inputs_processed", &self.inputs_processed)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use super::*;
+    use crate::{
+        create_workers, run_map_reduce_with_pool, GenericInputData, LineCountWorker,
+        MapReduceError, RunOptions, StringInputData, ThreadPool,
+    };
+
+    // Flips the flag while it is being read, as a Ctrl-C handler would mid-run.
+    struct InterruptingInput(CancellationToken);
+
+    impl GenericInputData<String> for InterruptingInput {
+        fn read(&self) -> Result<String, MapReduceError> {
+            self.0.cancel();
+            Ok("interrupted\n".to_string())
+        }
+    }
+
+    fn inputs(token: &CancellationToken) -> Vec<Box<dyn GenericInputData<String>>> {
+        let mut inputs: Vec<Box<dyn GenericInputData<String>>> = vec![
+            Box::new(StringInputData::new("one\n")),
+            Box::new(InterruptingInput(token.clone())),
+        ];
+        inputs.extend((0..3).map(|_| {
+            Box::new(StringInputData::new("skipped\n")) as Box<dyn GenericInputData<String>>
+        }));
+        inputs
+    }
+
+    #[test]
+    fn a_flag_flipped_mid_run_reduces_what_finished() {
+        let flag = Arc::new(AtomicBool::new(false));
+        let workers = create_workers(inputs(&flag.clone().into()), LineCountWorker::new);
+        // One thread picks the workers up in order, so the ones after the
+        // interrupting input are never started.
+        let options = RunOptions::new().cancel_flag(flag);
+        let summary = run_map_reduce_with_pool(&ThreadPool::new(1), workers, options).unwrap();
+
+        assert_eq!(summary.result, 2);
+        assert_eq!((summary.inputs_processed, summary.cancelled), (2, 3));
+    }
+
+    #[test]
+    fn a_cancelled_token_fails_with_the_partial_result() {
+        let token = CancellationToken::new();
+        let workers = create_workers(inputs(&token), LineCountWorker::new);
+        let options = RunOptions::new().cancellation_token(token);
+        let outcome = run_map_reduce_with_pool(&ThreadPool::new(1), workers, options);
+
+        let Err(MapReduceError::Cancelled(Some(partial))) = outcome else {
+            panic!("expected a cancelled run");
+        };
+        assert_eq!(partial.inputs_processed(), 2);
+        assert_eq!(partial.downcast::<usize>(), Some(2));
+    }
+
+    struct SlowInput;
+
+    impl GenericInputData<String> for SlowInput {
+        fn read(&self) -> Result<String, MapReduceError> {
+            thread::sleep(Duration::from_millis(20));
+            Ok("line\n".to_string())
+        }
+    }
+
+    #[test]
+    fn cancelling_from_another_thread_returns_the_partial_result() {
+        let inputs: Vec<Box<dyn GenericInputData<String>>> = (0..20)
+            .map(|_| Box::new(SlowInput) as Box<dyn GenericInputData<String>>)
+            .collect();
+        let token = CancellationToken::new();
+        let canceller = {
+            let token = token.clone();
+            thread::spawn(move || {
+                thread::sleep(Duration::from_millis(50));
+                token.cancel();
+            })
+        };
+
+        let workers = create_workers(inputs, LineCountWorker::new);
+        let options = RunOptions::new().cancellation_token(token);
+        let outcome = run_map_reduce_with_pool(&ThreadPool::new(1), workers, options);
+        canceller.join().unwrap();
+
+        let Err(MapReduceError::Cancelled(Some(partial))) = outcome else {
+            panic!("expected a cancelled run");
+        };
+        let processed = partial.inputs_processed();
+        assert!((1..20).contains(&processed), "{processed} inputs processed");
+        assert_eq!(partial.downcast::<usize>(), Some(processed));
+    }
+}
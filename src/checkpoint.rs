@@ -0,0 +1,233 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs,
+    ops::Add,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Instant,
+};
+
+use parking_lot::Mutex;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    map_each, MapReduceError, MapReducer, PhaseTimings, RunOptions, RunSummary, ThreadPool,
+};
+
+const CHECKPOINT_INTERVAL: usize = 64;
+
+// A path and which of the inputs with that path it is.
+type InputKey = (PathBuf, usize);
+
+/// The result of one mapped input. Several inputs can share a path, such as
+/// the chunks from [`chunk_file`](crate::chunk_file) or archive entries with
+/// the same name, so `occurrence` records which of them this is, counting
+/// from 0 in the order the inputs were listed.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CheckpointEntry<T = usize> {
+    pub path: PathBuf,
+    pub occurrence: usize,
+    pub count: T,
+}
+
+pub fn save_checkpoint<T: Serialize>(
+    path: impl AsRef<Path>,
+    completed: &[CheckpointEntry<T>],
+) -> Result<(), MapReduceError> {
+    let path = path.as_ref();
+    let json = serde_json::to_string(completed)
+        .map_err(|error| MapReduceError::InvalidCheckpoint(path.to_path_buf(), error))?;
+    write_atomically(path, &json)
+}
+
+// Writing beside the file and renaming over it means a crash while saving
+// leaves the previous contents intact.
+pub(crate) fn write_atomically(path: &Path, contents: &str) -> Result<(), MapReduceError> {
+    let partial = path.with_extension("partial");
+    fs::write(&partial, contents)?;
+    fs::rename(partial, path)?;
+    Ok(())
+}
+
+pub fn load_checkpoint<T: DeserializeOwned>(
+    path: impl AsRef<Path>,
+) -> Result<Vec<CheckpointEntry<T>>, MapReduceError> {
+    let path = path.as_ref();
+    let json = fs::read_to_string(path)?;
+    serde_json::from_str(&json)
+        .map_err(|error| MapReduceError::InvalidCheckpoint(path.to_path_buf(), error))
+}
+
+/// Like [`run_map_reduce`](crate::run_map_reduce), but the result of every
+/// mapped input is saved to `checkpoint_path` in batches, and inputs already
+/// recorded there by an earlier run are not mapped again. Inputs are matched
+/// up by path and position among inputs with that path, so a resumed run must
+/// be given the inputs in the same order.
+///
+/// Per-input results are added together, so this only suits workers whose
+/// output is a sum.
+pub fn run_map_reduce_resumable<W>(
+    workers: Vec<Arc<Mutex<W>>>,
+    checkpoint_path: impl AsRef<Path>,
+) -> Result<RunSummary<W::Output>, MapReduceError>
+where
+    W: MapReducer + 'static,
+    W::Output: Add<Output = W::Output> + Serialize + DeserializeOwned,
+{
+    let started = Instant::now();
+    let checkpoint_path = checkpoint_path.as_ref();
+    let mut completed: Vec<CheckpointEntry<W::Output>> = if checkpoint_path.exists() {
+        load_checkpoint(checkpoint_path)?
+    } else {
+        Vec::new()
+    };
+    if !completed.is_empty() {
+        log::info!(
+            "resuming with {} inputs from {}",
+            completed.len(),
+            checkpoint_path.display()
+        );
+    }
+
+    let mut occurrences: HashMap<PathBuf, usize> = HashMap::new();
+    let keyed = workers.into_iter().map(|worker| {
+        let key = worker.lock().input_path().map(|path| {
+            let occurrence = occurrences.entry(path.to_path_buf()).or_insert(0);
+            *occurrence += 1;
+            (path.to_path_buf(), *occurrence - 1)
+        });
+        (key, worker)
+    });
+    let done: HashSet<InputKey> = completed
+        .iter()
+        .map(|entry| (entry.path.clone(), entry.occurrence))
+        .collect();
+    let pending: Vec<(Option<InputKey>, Arc<Mutex<W>>)> = keyed
+        .filter(|(key, _)| !key.as_ref().is_some_and(|key| done.contains(key)))
+        .collect();
+
+    let pool = ThreadPool::default();
+    // Inputs without a path cannot be matched up on a later run, so they are
+    // mapped every time rather than recorded.
+    let mut unrecorded = Vec::new();
+    let mut failures = Vec::new();
+    let mut timed_out = 0;
+    let mut cancelled = 0;
+    for batch in pending.chunks(CHECKPOINT_INTERVAL) {
+        let workers = batch.iter().map(|(_, worker)| Arc::clone(worker)).collect();
+        let mapped = map_each(&pool, workers, RunOptions::new());
+        for (&index, worker) in mapped.indices.iter().zip(&mapped.workers) {
+            match &batch[index].0 {
+                Some((path, occurrence)) => completed.push(CheckpointEntry {
+                    path: path.clone(),
+                    occurrence: *occurrence,
+                    count: worker.get_result(),
+                }),
+                None => unrecorded.push(worker.get_result()),
+            }
+        }
+        failures.extend(mapped.failures);
+        timed_out += mapped.timed_out;
+        cancelled += mapped.cancelled;
+        save_checkpoint(checkpoint_path, &completed)?;
+    }
+    let map_duration = started.elapsed();
+
+    let inputs_processed = completed.len() + unrecorded.len();
+    if inputs_processed == 0 && !failures.is_empty() {
+        let (_, error) = failures.swap_remove(0);
+        return Err(error);
+    }
+
+    let reduce_started = Instant::now();
+    let result = completed
+        .into_iter()
+        .map(|entry| entry.count)
+        .chain(unrecorded)
+        .fold(W::identity().get_result(), |total, count| total + count);
+    let timings = PhaseTimings {
+        map_duration,
+        reduce_duration: reduce_started.elapsed(),
+        total_duration: started.elapsed(),
+    };
+
+    Ok(RunSummary {
+        result,
+        inputs_processed,
+        inputs_failed: failures.len(),
+        timed_out,
+        cancelled,
+        timings,
+        failures,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{chunk_file, create_workers, generate_inputs, test_util::TempDir, LineCountWorker};
+
+    #[test]
+    fn a_resumed_run_only_reads_unprocessed_inputs() {
+        let dir = TempDir::new();
+        let checkpoint = dir.path().join("checkpoint.json");
+        let inputs = TempDir::new();
+        let a = inputs.write("a.txt", "one\ntwo");
+        let b = inputs.write("b.txt", "three");
+        inputs.write("c.txt", "four\nfive\nsix");
+        inputs.write("d.txt", "seven");
+
+        let mut first_half = generate_inputs(inputs.path()).unwrap();
+        first_half.truncate(2);
+        let workers = create_workers(first_half, LineCountWorker::new);
+        let halfway = run_map_reduce_resumable(workers, &checkpoint).unwrap();
+        assert_eq!(halfway.result, 3);
+
+        // Inputs already recorded would fail the run if they were read again.
+        let all_inputs = generate_inputs(inputs.path()).unwrap();
+        fs::remove_file(a).unwrap();
+        fs::remove_file(b).unwrap();
+        let workers = create_workers(all_inputs, LineCountWorker::new);
+        let resumed = run_map_reduce_resumable(workers, &checkpoint).unwrap();
+        assert_eq!((resumed.result, resumed.inputs_failed), (7, 0));
+        assert_eq!(load_checkpoint::<usize>(&checkpoint).unwrap().len(), 4);
+    }
+
+    #[test]
+    fn chunks_of_one_file_are_checkpointed_separately() {
+        let dir = TempDir::new();
+        let checkpoint = dir.path().join("checkpoint.json");
+        let path = dir.write("big.txt", "one\ntwo\nthree\nfour\nfive\nsix\n");
+
+        let mut first_chunk = chunk_file(&path, 10).unwrap();
+        first_chunk.truncate(1);
+        let workers = create_workers(first_chunk, LineCountWorker::new);
+        assert_eq!(
+            run_map_reduce_resumable(workers, &checkpoint)
+                .unwrap()
+                .result,
+            3
+        );
+
+        let workers = create_workers(chunk_file(&path, 10).unwrap(), LineCountWorker::new);
+        let resumed = run_map_reduce_resumable(workers, &checkpoint).unwrap();
+        assert_eq!(resumed.result, 6);
+        let occurrences: Vec<usize> = load_checkpoint::<usize>(&checkpoint)
+            .unwrap()
+            .into_iter()
+            .map(|entry| entry.occurrence)
+            .collect();
+        assert_eq!(occurrences, [0, 1, 2]);
+    }
+
+    #[test]
+    fn a_corrupt_checkpoint_names_its_file() {
+        let dir = TempDir::new();
+        let path = dir.write("checkpoint.json", "not json");
+        let error = load_checkpoint::<usize>(&path).unwrap_err();
+        assert!(
+            matches!(&error, MapReduceError::InvalidCheckpoint(invalid, _) if *invalid == path)
+        );
+        assert!(error.to_string().contains("checkpoint.json"));
+    }
+}
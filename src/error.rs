@@ -0,0 +1,161 @@
+use std::{error::Error, fmt, io, path::PathBuf, string::FromUtf8Error, time::Duration};
+
+use crate::PartialResult;
+
+#[derive(Debug)]
+pub enum MapReduceError {
+    Io(io::Error),
+    Utf8(FromUtf8Error),
+    Http(Box<ureq::Error>),
+    InvalidPattern(regex::Error),
+    EmptyInput,
+    MissingDataDir,
+    DirectoryNotFound(PathBuf),
+    MissingInputs(Vec<PathBuf>),
+    InvalidThreadCount,
+    InvalidSampleFraction(f64),
+    UnknownWorker(String),
+    NotACount(&'static str),
+    TimedOut(Duration),
+    Cancelled(Option<PartialResult>),
+    Panicked(String),
+    AlreadyConsumed,
+    InvalidCheckpoint(PathBuf, serde_json::Error),
+    InvalidProgress(PathBuf, serde_json::Error),
+    Zip(zip::result::ZipError),
+    EncryptedEntry(PathBuf),
+    NotByteAddressable(PathBuf),
+    MismatchedOutputs(&'static str, &'static str),
+    UnmergeableOutput(&'static str),
+}
+
+impl fmt::Display for MapReduceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "I/O error: {error}"),
+            Self::Utf8(error) => write!(f, "input is not valid UTF-8: {error}"),
+            Self::Http(error) => write!(f, "HTTP request failed: {error}"),
+            Self::InvalidPattern(error) => write!(f, "invalid pattern: {error}"),
+            Self::EmptyInput => write!(f, "there were no inputs to process"),
+            Self::MissingDataDir => write!(f, "no data directory was configured"),
+            Self::DirectoryNotFound(path) => {
+                write!(f, "data directory {} does not exist", path.display())
+            }
+            Self::MissingInputs(paths) => {
+                write!(f, "input files do not exist:")?;
+                for path in paths {
+                    write!(f, " {}", path.display())?;
+                }
+                Ok(())
+            }
+            Self::InvalidThreadCount => write!(f, "thread count must be greater than zero"),
+            Self::InvalidSampleFraction(fraction) => {
+                write!(
+                    f,
+                    "sample fraction must be above 0 and at most 1, not {fraction}"
+                )
+            }
+            Self::UnknownWorker(name) => write!(f, "unknown worker `{name}`"),
+            Self::NotACount(name) => write!(f, "the `{name}` worker does not produce a count"),
+            Self::TimedOut(timeout) => write!(f, "worker did not finish within {timeout:?}"),
+            Self::Cancelled(None) => write!(f, "the run was cancelled"),
+            Self::Cancelled(Some(partial)) => write!(
+                f,
+                "the run was cancelled after {} inputs",
+                partial.inputs_processed()
+            ),
+            Self::Panicked(message) => write!(f, "worker panicked during map: {message}"),
+            Self::AlreadyConsumed => write!(f, "input can only be read once"),
+            Self::InvalidCheckpoint(path, error) => {
+                write!(f, "invalid checkpoint {}: {error}", path.display())
+            }
+            Self::InvalidProgress(path, error) => {
+                write!(
+                    f,
+                    "invalid incremental progress {}: {error}",
+                    path.display()
+                )
+            }
+            Self::Zip(error) => write!(f, "could not read zip archive: {error}"),
+            Self::EncryptedEntry(path) => {
+                write!(f, "{} is password-protected", path.display())
+            }
+            Self::NotByteAddressable(path) => write!(
+                f,
+                "{} is compressed or UTF-16, so it cannot be read by byte offset",
+                path.display()
+            ),
+            Self::MismatchedOutputs(left, right) => {
+                write!(f, "cannot combine a {left} output with a {right} output")
+            }
+            Self::UnmergeableOutput(kind) => write!(f, "{kind} outputs cannot be combined"),
+        }
+    }
+}
+
+impl Error for MapReduceError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Utf8(error) => Some(error),
+            Self::Http(error) => Some(error),
+            Self::InvalidPattern(error) => Some(error),
+            Self::InvalidCheckpoint(_, error) | Self::InvalidProgress(_, error) => Some(error),
+            Self::Zip(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for MapReduceError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<FromUtf8Error> for MapReduceError {
+    fn from(error: FromUtf8Error) -> Self {
+        Self::Utf8(error)
+    }
+}
+
+impl From<ureq::Error> for MapReduceError {
+    fn from(error: ureq::Error) -> Self {
+        Self::Http(Box::new(error))
+    }
+}
+
+impl From<regex::Error> for MapReduceError {
+    fn from(error: regex::Error) -> Self {
+        Self::InvalidPattern(error)
+    }
+}
+
+impl From<zip::result::ZipError> for MapReduceError {
+    fn from(error: zip::result::ZipError) -> Self {
+        Self::Zip(error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{test_util::TempDir, FileInputData, GenericInputData, MapReduceJob};
+
+    #[test]
+    fn a_missing_file_is_an_io_error() {
+        let dir = TempDir::new();
+        let input = FileInputData::new(dir.path().join("missing.txt"));
+        let error = GenericInputData::<String>::read(&input).unwrap_err();
+        assert!(matches!(&error, MapReduceError::Io(io) if io.kind() == io::ErrorKind::NotFound));
+        assert!(error.source().is_some());
+    }
+
+    #[test]
+    fn an_empty_directory_is_empty_input() {
+        let dir = TempDir::new();
+        let error = MapReduceJob::new().data_dir(dir.path()).run().unwrap_err();
+        assert!(matches!(error, MapReduceError::EmptyInput));
+        assert_eq!(error.to_string(), "there were no inputs to process");
+    }
+}
@@ -0,0 +1,103 @@
+use std::sync::{mpsc, Arc};
+
+use crate::{pool::catch_panics, GenericInputData, MapReduceError, ThreadPool};
+
+/// Maps every input with `map_fn` on the thread pool and folds the results
+/// with `reduce_fn`, starting from `identity`, without defining a worker.
+///
+/// ```
+/// use rs_map_reduce::{generate_inputs, run_with_fold};
+///
+/// # let dir = std::env::temp_dir().join(format!("rs_map_reduce-fold-doc-{}", std::process::id()));
+/// # std::fs::create_dir_all(&dir)?;
+/// std::fs::write(dir.join("a.txt"), "hello")?;
+/// std::fs::write(dir.join("b.txt"), "hello, world")?;
+///
+/// let inputs = generate_inputs::<String>(&dir)?;
+/// let bytes = run_with_fold(inputs, |content| content.len(), |a, b| a + b, 0)?;
+/// assert_eq!(bytes, 17);
+/// # std::fs::remove_dir_all(&dir)?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+pub fn run_with_fold<A, M, R>(
+    input_list: Vec<Box<dyn GenericInputData<String>>>,
+    map_fn: M,
+    reduce_fn: R,
+    identity: A,
+) -> Result<A, MapReduceError>
+where
+    A: Send + 'static,
+    M: Fn(&str) -> A + Send + Sync + 'static,
+    R: Fn(A, A) -> A,
+{
+    let pool = ThreadPool::default();
+    let map_fn = Arc::new(map_fn);
+    let (sender, receiver) = mpsc::channel();
+
+    for (index, input_data) in input_list.into_iter().enumerate() {
+        let map_fn = Arc::clone(&map_fn);
+        let sender = sender.clone();
+        pool.execute(move || {
+            let outcome = catch_panics(|| input_data.read().map(|content| map_fn(&content)));
+            if let Err(error) = &outcome {
+                match input_data.path() {
+                    Some(path) => log::warn!("failed to map {}: {error}", path.display()),
+                    None => log::warn!("failed to map input #{index}: {error}"),
+                }
+            }
+            sender
+                .send((index, outcome))
+                .expect("Failed to report mapped input");
+        });
+    }
+    drop(sender);
+
+    // Folding in input order keeps the result deterministic even when
+    // `reduce_fn` is not commutative.
+    let mut outcomes: Vec<(usize, Result<A, MapReduceError>)> = receiver.iter().collect();
+    outcomes.sort_by_key(|(index, _)| *index);
+
+    let mut result = identity;
+    let mut mapped = 0;
+    let mut first_error = None;
+    for (_, outcome) in outcomes {
+        match outcome {
+            Ok(value) => {
+                result = reduce_fn(result, value);
+                mapped += 1;
+            }
+            Err(error) => {
+                first_error.get_or_insert(error);
+            }
+        }
+    }
+
+    // As with workers, failed inputs are skipped unless nothing could be mapped.
+    match first_error {
+        Some(error) if mapped == 0 => Err(error),
+        _ => Ok(result),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{string_inputs, PanickingInput};
+
+    #[test]
+    fn a_panicking_input_or_map_fn_is_skipped() {
+        let mut inputs = string_inputs(&["hello", "boom", "world!"]);
+        inputs.push(Box::new(PanickingInput));
+
+        let bytes = run_with_fold(
+            inputs,
+            |content| {
+                assert_ne!(content, "boom", "map_fn exploded");
+                content.len()
+            },
+            |a, b| a + b,
+            0,
+        );
+        assert_eq!(bytes.unwrap(), 11);
+    }
+}
@@ -0,0 +1,92 @@
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+use serde::{Deserialize, Serialize};
+
+/// The hash used to compare content, by `create_workers_deduped_with` and
+/// `HyperLogLogWorker`.
+///
+/// `Default` is std's `DefaultHasher`, which is only guaranteed to be stable
+/// within one build of Rust; the others give the same hashes everywhere.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ContentHasher {
+    #[default]
+    Default,
+    #[cfg(feature = "blake3")]
+    Blake3,
+    #[cfg(feature = "xxhash")]
+    Xxh3,
+}
+
+impl ContentHasher {
+    pub fn hash(&self, content: &str) -> u64 {
+        match self {
+            Self::Default => {
+                let mut hasher = DefaultHasher::new();
+                content.hash(&mut hasher);
+                hasher.finish()
+            }
+            #[cfg(feature = "blake3")]
+            Self::Blake3 => {
+                let digest = blake3::hash(content.as_bytes());
+                let mut prefix = [0; 8];
+                prefix.copy_from_slice(&digest.as_bytes()[..8]);
+                u64::from_le_bytes(prefix)
+            }
+            #[cfg(feature = "xxhash")]
+            Self::Xxh3 => xxhash_rust::xxh3::xxh3_64(content.as_bytes()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        create_workers_deduped_with, run, test_util::string_inputs, HyperLogLogWorker,
+        LineCountWorker,
+    };
+
+    const CONTENTS: [&str; 5] = ["same", "other", "same", "third", "other"];
+
+    // For each input, the index of the first input that shares its worker.
+    fn groups(hasher: ContentHasher) -> Vec<usize> {
+        let workers =
+            create_workers_deduped_with(string_inputs(&CONTENTS), LineCountWorker::new, hasher);
+        workers
+            .iter()
+            .map(|worker| {
+                workers
+                    .iter()
+                    .position(|other| Arc::ptr_eq(worker, other))
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    fn hashers() -> Vec<ContentHasher> {
+        vec![
+            ContentHasher::Default,
+            #[cfg(feature = "blake3")]
+            ContentHasher::Blake3,
+            #[cfg(feature = "xxhash")]
+            ContentHasher::Xxh3,
+        ]
+    }
+
+    #[test]
+    fn the_same_hasher_gives_the_same_groups() {
+        for hasher in hashers() {
+            assert_eq!(groups(hasher), [0, 1, 0, 3, 1], "{hasher:?}");
+            assert_eq!(groups(hasher), groups(hasher), "{hasher:?}");
+            assert_eq!(hasher.hash("same"), hasher.hash("same"));
+            assert_ne!(hasher.hash("same"), hasher.hash("other"));
+
+            let distinct = run(string_inputs(&["a\nb", "b\nc"]), |input| {
+                HyperLogLogWorker::with_hasher(input, hasher)
+            });
+            assert_eq!(distinct.unwrap(), 3, "{hasher:?}");
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use std::{
+    collections::HashMap,
+    fs,
+    ops::Add,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use parking_lot::Mutex;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+use crate::{
+    checkpoint::write_atomically, map_parallel, FileInputData, GenericInputData, MapReduceError,
+    MapReducer, RunOptions, StringInputData, ThreadPool,
+};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileProgress<T> {
+    pub path: PathBuf,
+    pub offset: u64,
+    pub total: T,
+}
+
+pub fn save_progress<T: Serialize>(
+    path: impl AsRef<Path>,
+    progress: &[FileProgress<T>],
+) -> Result<(), MapReduceError> {
+    let path = path.as_ref();
+    let json = serde_json::to_string(progress)
+        .map_err(|error| MapReduceError::InvalidProgress(path.to_path_buf(), error))?;
+    write_atomically(path, &json)
+}
+
+pub fn load_progress<T: DeserializeOwned>(
+    path: impl AsRef<Path>,
+) -> Result<Vec<FileProgress<T>>, MapReduceError> {
+    let path = path.as_ref();
+    let json = fs::read_to_string(path)?;
+    serde_json::from_str(&json)
+        .map_err(|error| MapReduceError::InvalidProgress(path.to_path_buf(), error))
+}
+
+/// Maps only the lines appended to each file since the offset recorded for it
+/// in `state_path`, adds the result to the total recorded alongside, and saves
+/// the new offsets and totals for the next run. The first run, and any run
+/// after a file was truncated, maps the whole file.
+///
+/// As with [`run_map_reduce_resumable`](crate::run_map_reduce_resumable), this
+/// only suits workers whose output is a sum. The result is the total across
+/// every file in the state, and files that cannot be read are skipped and keep
+/// their recorded progress.
+pub fn run_map_reduce_incremental<W, F>(
+    files: Vec<FileInputData>,
+    make_worker: F,
+    state_path: impl AsRef<Path>,
+) -> Result<W::Output, MapReduceError>
+where
+    W: MapReducer + 'static,
+    W::Output: Add<Output = W::Output> + Clone + Serialize + DeserializeOwned,
+    F: Fn(Arc<dyn GenericInputData<String>>) -> W,
+{
+    let state_path = state_path.as_ref();
+    let mut progress: HashMap<PathBuf, FileProgress<W::Output>> = if state_path.exists() {
+        load_progress(state_path)?
+            .into_iter()
+            .map(|file| (file.path.clone(), file))
+            .collect()
+    } else {
+        HashMap::new()
+    };
+
+    let mut pending = Vec::new();
+    let mut workers = Vec::new();
+    for file in files {
+        let recorded = progress.get(file.path());
+        let offset = recorded.map_or(0, |recorded| recorded.offset);
+        match file.read_since(offset) {
+            Ok((appended, end)) => {
+                // An offset that went backwards means the file was read from
+                // the start again, so its old total no longer applies.
+                let prior = recorded
+                    .filter(|_| end >= offset)
+                    .map(|recorded| recorded.total.clone());
+                pending.push((file.path().to_path_buf(), end, prior));
+                workers.push(Arc::new(Mutex::new(make_worker(Arc::new(
+                    StringInputData::new(appended),
+                )))));
+            }
+            Err(error) => log::warn!("skipping {}: {error}", file.path().display()),
+        }
+    }
+
+    let pool = ThreadPool::default();
+    let outcomes = map_parallel(&pool, &workers, RunOptions::new());
+    for ((path, offset, prior), (worker, outcome)) in
+        pending.into_iter().zip(workers.iter().zip(outcomes))
+    {
+        if let Err(error) = outcome {
+            log::warn!("skipping {}: {error}", path.display());
+            continue;
+        }
+        let appended = worker.lock().get_result();
+        let total = match prior {
+            Some(prior) => prior + appended,
+            None => appended,
+        };
+        progress.insert(
+            path.clone(),
+            FileProgress {
+                path,
+                offset,
+                total,
+            },
+        );
+    }
+
+    let mut progress: Vec<FileProgress<W::Output>> = progress.into_values().collect();
+    progress.sort_by(|a, b| a.path.cmp(&b.path));
+    save_progress(state_path, &progress)?;
+
+    Ok(progress
+        .into_iter()
+        .map(|file| file.total)
+        .fold(W::identity().get_result(), |total, count| total + count))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs::OpenOptions, io::Write};
+
+    use super::*;
+    use crate::{test_util::TempDir, LineCountWorker};
+
+    fn append(path: &Path, content: &str) {
+        let mut file = OpenOptions::new().append(true).open(path).unwrap();
+        file.write_all(content.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn a_second_run_only_counts_appended_lines() {
+        let dir = TempDir::new();
+        let log = dir.write("app.log", "one\ntwo\nthree\n");
+        let other = dir.write("other.log", "a\n");
+        let state = dir.path().join("state.json");
+        let files = || {
+            vec![
+                FileInputData::new(log.clone()),
+                FileInputData::new(other.clone()),
+            ]
+        };
+        let incremental = || run_map_reduce_incremental(files(), LineCountWorker::new, &state);
+
+        assert_eq!(incremental().unwrap(), 4);
+        // A line still being written is left for the run after it is finished.
+        append(&log, "four\nfive\nsix, half written");
+        assert_eq!(incremental().unwrap(), 6);
+        append(&log, " and done\n");
+        assert_eq!(incremental().unwrap(), 7);
+        assert_eq!(incremental().unwrap(), 7);
+
+        let progress = load_progress::<usize>(&state).unwrap();
+        assert_eq!(
+            progress,
+            [
+                FileProgress {
+                    path: log.clone(),
+                    offset: fs::metadata(&log).unwrap().len(),
+                    total: 6
+                },
+                FileProgress {
+                    path: other.clone(),
+                    offset: 2,
+                    total: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn a_truncated_file_is_counted_from_the_start() {
+        let dir = TempDir::new();
+        let log = dir.write("app.log", "one\ntwo\nthree\n");
+        let state = dir.path().join("state.json");
+        let incremental = || {
+            run_map_reduce_incremental(
+                vec![FileInputData::new(log.clone())],
+                LineCountWorker::new,
+                &state,
+            )
+        };
+
+        assert_eq!(incremental().unwrap(), 3);
+        fs::write(&log, "rotated\n").unwrap();
+        assert_eq!(incremental().unwrap(), 1);
+        append(&log, "again\n");
+        assert_eq!(incremental().unwrap(), 2);
+    }
+
+    #[test]
+    fn a_corrupt_progress_file_names_its_file() {
+        let dir = TempDir::new();
+        let path = dir.write("state.json", "not json");
+        let error = load_progress::<usize>(&path).unwrap_err();
+        assert!(matches!(&error, MapReduceError::InvalidProgress(invalid, _) if *invalid == path));
+        assert!(error.to_string().contains("state.json"));
+    }
+}
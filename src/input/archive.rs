@@ -0,0 +1,189 @@
+use std::{
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use tar::{Archive, EntryType};
+use zip::ZipArchive;
+
+use super::{file::decode_file_contents, GenericInputData};
+use crate::MapReduceError;
+
+// An entry of a tar or zip archive, read into memory while the archive was
+// walked.
+pub struct ArchiveEntryInputData {
+    entry_path: PathBuf,
+    bytes: Vec<u8>,
+}
+
+impl ArchiveEntryInputData {
+    pub fn new(entry_path: PathBuf, bytes: Vec<u8>) -> Self {
+        Self { entry_path, bytes }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.entry_path
+    }
+}
+
+impl<T> GenericInputData<T> for ArchiveEntryInputData
+where
+    T: From<String>,
+{
+    fn read(&self) -> Result<T, MapReduceError> {
+        Ok(T::from(decode_file_contents(
+            &self.entry_path,
+            self.bytes.clone(),
+        )?))
+    }
+
+    fn path(&self) -> Option<&Path> {
+        Some(&self.entry_path)
+    }
+}
+
+pub fn generate_inputs_from_tar<T>(
+    tar_path: impl AsRef<Path>,
+) -> Result<Vec<Box<dyn GenericInputData<T>>>, MapReduceError>
+where
+    T: From<String>,
+{
+    let tar_path = tar_path.as_ref();
+    let mut archive = Archive::new(File::open(tar_path)?);
+
+    let mut inputs: Vec<Box<dyn GenericInputData<T>>> = Vec::new();
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if entry.header().entry_type() != EntryType::Regular {
+            continue;
+        }
+
+        let entry_path = entry.path()?.into_owned();
+        let mut bytes = Vec::new();
+        entry.read_to_end(&mut bytes)?;
+        inputs.push(Box::new(ArchiveEntryInputData::new(entry_path, bytes)));
+    }
+
+    log::info!("found {} inputs in {}", inputs.len(), tar_path.display());
+    Ok(inputs)
+}
+
+pub fn generate_inputs_from_zip<T>(
+    zip_path: impl AsRef<Path>,
+) -> Result<Vec<Box<dyn GenericInputData<T>>>, MapReduceError>
+where
+    T: From<String>,
+{
+    let zip_path = zip_path.as_ref();
+    let mut archive = ZipArchive::new(File::open(zip_path)?)?;
+
+    let mut inputs: Vec<Box<dyn GenericInputData<T>>> = Vec::new();
+    for index in 0..archive.len() {
+        // The raw entry can be inspected without a password, so encrypted
+        // entries are reported by name rather than as a decryption failure.
+        let entry_path = {
+            let entry = archive.by_index_raw(index)?;
+            if entry.is_dir() {
+                continue;
+            }
+            let entry_path = PathBuf::from(entry.name()?.as_ref());
+            if entry.encrypted() {
+                return Err(MapReduceError::EncryptedEntry(entry_path));
+            }
+            entry_path
+        };
+
+        let mut bytes = Vec::new();
+        archive.by_index(index)?.read_to_end(&mut bytes)?;
+        inputs.push(Box::new(ArchiveEntryInputData::new(entry_path, bytes)));
+    }
+
+    log::info!("found {} inputs in {}", inputs.len(), zip_path.display());
+    Ok(inputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use zip::{unstable::write::FileOptionsExt, write::SimpleFileOptions, ZipWriter};
+
+    use super::*;
+    use crate::{run, test_util::TempDir, LineCountWorker};
+
+    #[test]
+    fn counts_lines_in_the_regular_files_of_a_tar() {
+        let dir = TempDir::new();
+        let tar_path = dir.path().join("corpus.tar");
+        let mut builder = tar::Builder::new(File::create(&tar_path).unwrap());
+        let mut add = |path: &str, entry_type: EntryType, content: &[u8]| {
+            let mut header = tar::Header::new_gnu();
+            header.set_entry_type(entry_type);
+            header.set_size(content.len() as u64);
+            header.set_mode(0o644);
+            builder.append_data(&mut header, path, content).unwrap();
+        };
+        add("docs/", EntryType::Directory, b"");
+        add("docs/a.txt", EntryType::Regular, b"one\ntwo\nthree\n");
+        add("docs/b.txt", EntryType::Regular, b"four\nfive");
+        builder.finish().unwrap();
+
+        let inputs = generate_inputs_from_tar::<String>(&tar_path).unwrap();
+        let paths: Vec<_> = inputs.iter().map(|input| input.path().unwrap()).collect();
+        assert_eq!(paths, [Path::new("docs/a.txt"), Path::new("docs/b.txt")]);
+        assert_eq!(run(inputs, LineCountWorker::new).unwrap(), 5);
+    }
+
+    fn write_zip(path: &Path, entries: &[(&str, &[u8], bool)]) {
+        let mut writer = ZipWriter::new(File::create(path).unwrap());
+        writer
+            .add_directory("docs/", SimpleFileOptions::default())
+            .unwrap();
+        for &(name, content, encrypted) in entries {
+            let mut options = SimpleFileOptions::default();
+            if encrypted {
+                options = options.with_deprecated_encryption(b"secret").unwrap();
+            }
+            writer.start_file(name, options).unwrap();
+            writer.write_all(content).unwrap();
+        }
+        writer.finish().unwrap();
+    }
+
+    #[test]
+    fn counts_lines_in_the_files_of_a_zip() {
+        let dir = TempDir::new();
+        let zip_path = dir.path().join("corpus.zip");
+        write_zip(
+            &zip_path,
+            &[
+                ("docs/a.txt", b"one\ntwo\nthree\n", false),
+                ("docs/b.txt", b"four\nfive", false),
+            ],
+        );
+
+        let inputs = generate_inputs_from_zip::<String>(&zip_path).unwrap();
+        assert_eq!(inputs.len(), 2);
+        assert_eq!(run(inputs, LineCountWorker::new).unwrap(), 5);
+    }
+
+    #[test]
+    fn encrypted_zip_entries_are_reported_by_name() {
+        let dir = TempDir::new();
+        let zip_path = dir.path().join("locked.zip");
+        write_zip(
+            &zip_path,
+            &[
+                ("docs/open.txt", b"one\n", false),
+                ("docs/locked.txt", b"two\n", true),
+            ],
+        );
+
+        let result = generate_inputs_from_zip::<String>(&zip_path);
+        assert!(matches!(
+            result,
+            Err(MapReduceError::EncryptedEntry(path)) if path == Path::new("docs/locked.txt")
+        ));
+    }
+}
@@ -0,0 +1,34 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use super::GenericInputData;
+use crate::MapReduceError;
+
+// `FileInputData` can already produce a `Vec<u8>`, but only by way of decoded
+// text, which mangles anything that is not valid UTF-8.
+pub struct BinaryFileInputData {
+    file_path: PathBuf,
+}
+
+impl BinaryFileInputData {
+    pub fn new(file_path: PathBuf) -> Self {
+        Self { file_path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.file_path
+    }
+}
+
+impl GenericInputData<Vec<u8>> for BinaryFileInputData {
+    fn read(&self) -> Result<Vec<u8>, MapReduceError> {
+        log::debug!("reading {}", self.file_path.display());
+        Ok(fs::read(&self.file_path)?)
+    }
+
+    fn path(&self) -> Option<&Path> {
+        Some(&self.file_path)
+    }
+}
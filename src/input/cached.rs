@@ -0,0 +1,95 @@
+use std::{path::Path, sync::Arc};
+
+use parking_lot::Mutex;
+
+use super::GenericInputData;
+use crate::MapReduceError;
+
+pub struct CachedInputData<T = String> {
+    input_data: Arc<dyn GenericInputData<T>>,
+    content: Mutex<Option<T>>,
+}
+
+impl<T> CachedInputData<T> {
+    pub fn new(input_data: Arc<dyn GenericInputData<T>>) -> Self {
+        Self {
+            input_data,
+            content: Mutex::new(None),
+        }
+    }
+
+    pub fn clear(&self) {
+        self.content.lock().take();
+    }
+}
+
+impl<T> GenericInputData<T> for CachedInputData<T>
+where
+    T: Clone + Send,
+{
+    fn read(&self) -> Result<T, MapReduceError> {
+        let mut content = self.content.lock();
+        if let Some(content) = content.as_ref() {
+            return Ok(content.clone());
+        }
+        let data = self.input_data.read()?;
+        *content = Some(data.clone());
+        Ok(data)
+    }
+
+    fn path(&self) -> Option<&Path> {
+        self.input_data.path()
+    }
+}
+
+// Holds content that was already read, e.g. to hash it, so that the first read
+// does not touch the input again. The content is handed over rather than kept,
+// and any later read goes back to the input.
+pub(crate) struct PrefetchedInputData<T = String> {
+    input_data: Arc<dyn GenericInputData<T>>,
+    content: Mutex<Option<T>>,
+}
+
+impl<T> PrefetchedInputData<T> {
+    pub(crate) fn new(input_data: Arc<dyn GenericInputData<T>>, content: T) -> Self {
+        Self {
+            input_data,
+            content: Mutex::new(Some(content)),
+        }
+    }
+}
+
+impl<T: Send> GenericInputData<T> for PrefetchedInputData<T> {
+    fn read(&self) -> Result<T, MapReduceError> {
+        match self.content.lock().take() {
+            Some(content) => Ok(content),
+            None => self.input_data.read(),
+        }
+    }
+
+    fn path(&self) -> Option<&Path> {
+        self.input_data.path()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+
+    use super::*;
+    use crate::test_util::CountingInput;
+
+    #[test]
+    fn the_source_is_read_once_across_reads() {
+        let (input, reads) = CountingInput::new("cached");
+        let cached = CachedInputData::new(Arc::new(input));
+
+        assert_eq!(cached.read().unwrap(), "cached");
+        assert_eq!(cached.read().unwrap(), "cached");
+        assert_eq!(reads.load(Ordering::SeqCst), 1);
+
+        cached.clear();
+        assert_eq!(cached.read().unwrap(), "cached");
+        assert_eq!(reads.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,124 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+use super::{
+    file::{is_gzip, utf16_encoding},
+    GenericInputData,
+};
+use crate::MapReduceError;
+
+pub struct FileChunkInputData {
+    file_path: PathBuf,
+    start: u64,
+    end: u64,
+}
+
+impl FileChunkInputData {
+    pub fn new(file_path: PathBuf, start: u64, end: u64) -> Self {
+        Self {
+            file_path,
+            start,
+            end,
+        }
+    }
+
+    pub fn range(&self) -> (u64, u64) {
+        (self.start, self.end)
+    }
+}
+
+impl<T> GenericInputData<T> for FileChunkInputData
+where
+    T: From<String>,
+{
+    fn read(&self) -> Result<T, MapReduceError> {
+        let mut file = File::open(&self.file_path)?;
+        file.seek(SeekFrom::Start(self.start))?;
+
+        let mut bytes = Vec::new();
+        file.take(self.end - self.start).read_to_end(&mut bytes)?;
+        Ok(T::from(String::from_utf8_lossy(&bytes).into_owned()))
+    }
+
+    fn path(&self) -> Option<&Path> {
+        Some(&self.file_path)
+    }
+}
+
+pub fn chunk_file(
+    path: impl AsRef<Path>,
+    chunk_size: usize,
+) -> Result<Vec<Box<dyn GenericInputData<String>>>, MapReduceError> {
+    let path = path.as_ref();
+    let mut reader = BufReader::new(File::open(path)?);
+    // Chunks are decoded on their own, which only works for text that can be
+    // cut on any newline byte.
+    if is_gzip(path) || utf16_encoding(reader.fill_buf()?).is_some() {
+        return Err(MapReduceError::NotByteAddressable(path.to_path_buf()));
+    }
+    let len = reader.get_ref().metadata()?.len();
+    let chunk_size = chunk_size.max(1) as u64;
+
+    let mut chunks: Vec<Box<dyn GenericInputData<String>>> = Vec::new();
+    let mut start = 0;
+    while start < len {
+        let mut end = (start + chunk_size).min(len);
+        if end < len {
+            // Extend the chunk up to and including the next newline so that no
+            // line is split. Starting at `end - 1` keeps a chunk that already
+            // ends on a newline unchanged.
+            reader.seek(SeekFrom::Start(end - 1))?;
+            let mut line_tail = Vec::new();
+            end = end - 1 + reader.read_until(b'\n', &mut line_tail)? as u64;
+        }
+        chunks.push(Box::new(FileChunkInputData::new(
+            path.to_path_buf(),
+            start,
+            end,
+        )));
+        start = end;
+    }
+
+    Ok(chunks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{run, test_util::TempDir, FileInputData, LineCountWorker};
+
+    #[test]
+    fn three_chunks_count_the_same_lines_as_the_whole_file() {
+        let dir = TempDir::new();
+        let path = dir.write("big.txt", "one\ntwo\nthree\nfour\nfive\nsix\n");
+
+        let chunks = chunk_file(&path, 10).unwrap();
+        assert_eq!(chunks.len(), 3);
+        let contents: Vec<String> = chunks.iter().map(|chunk| chunk.read().unwrap()).collect();
+        assert_eq!(contents, ["one\ntwo\nthree\n", "four\nfive\n", "six\n"]);
+
+        let whole: Vec<Box<dyn GenericInputData<String>>> =
+            vec![Box::new(FileInputData::new(path))];
+        assert_eq!(
+            run(chunks, LineCountWorker::new).unwrap(),
+            run(whole, LineCountWorker::new).unwrap()
+        );
+    }
+
+    #[test]
+    fn compressed_and_utf16_files_are_rejected() {
+        let dir = TempDir::new();
+        let gzipped = dir.write("big.txt.gz", [0x1f, 0x8b, 0x08, 0x00]);
+        let utf16 = dir.write("wide.txt", [0xff, 0xfe, b'a', 0, b'\n', 0]);
+
+        for path in [gzipped, utf16] {
+            let error = chunk_file(&path, 10).err().unwrap();
+            assert!(
+                matches!(error, MapReduceError::NotByteAddressable(rejected) if rejected == path)
+            );
+        }
+    }
+}
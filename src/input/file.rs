@@ -0,0 +1,311 @@
+use std::{
+    fs::{self, File},
+    io::{self, BufRead, BufReader, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+};
+
+use encoding_rs::{Encoding, UTF_16BE, UTF_16LE};
+use flate2::read::GzDecoder;
+
+use super::{GenericInputData, LineIter};
+use crate::MapReduceError;
+
+pub struct FileInputData {
+    file_path: PathBuf,
+    buffer_size: Option<usize>,
+}
+
+impl FileInputData {
+    pub fn new(file_path: PathBuf) -> Self {
+        Self {
+            file_path,
+            buffer_size: None,
+        }
+    }
+
+    /// Reads the file `buffer_size` bytes at a time, both when it is read
+    /// whole and when its lines are streamed.
+    pub fn with_buffer_size(file_path: PathBuf, buffer_size: usize) -> Self {
+        Self {
+            file_path,
+            buffer_size: Some(buffer_size),
+        }
+    }
+
+    fn open(&self) -> Result<BufReader<File>, MapReduceError> {
+        let file = File::open(&self.file_path)?;
+        Ok(match self.buffer_size {
+            // The first fill has to be large enough to hold a byte order mark.
+            Some(buffer_size) => BufReader::with_capacity(buffer_size.max(3), file),
+            None => BufReader::new(file),
+        })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.file_path
+    }
+
+    /// Reads the complete lines appended since byte `offset`, returning them
+    /// with the offset to pass next time. A trailing line that is still being
+    /// written is left for the next read. A file now shorter than `offset` has
+    /// been truncated or rotated, so it is read from the start again, and the
+    /// returned offset is then below the one passed in.
+    pub fn read_since(&self, offset: u64) -> Result<(String, u64), MapReduceError> {
+        // Offsets into compressed bytes don't line up with the text.
+        if is_gzip(&self.file_path) {
+            return Err(MapReduceError::NotByteAddressable(self.file_path.clone()));
+        }
+
+        let mut file = File::open(&self.file_path)?;
+        let start = if file.metadata()?.len() < offset {
+            log::info!(
+                "{} was truncated, reading it again",
+                self.file_path.display()
+            );
+            0
+        } else {
+            offset
+        };
+        file.seek(SeekFrom::Start(start))?;
+
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)?;
+        // UTF-16 is only recognised by its BOM, which a later read never sees.
+        if start == 0 && utf16_encoding(&bytes).is_some() {
+            return Err(MapReduceError::NotByteAddressable(self.file_path.clone()));
+        }
+        let complete = bytes
+            .iter()
+            .rposition(|&byte| byte == b'\n')
+            .map_or(0, |end| end + 1);
+        bytes.truncate(complete);
+        Ok((
+            String::from_utf8_lossy(&bytes).into_owned(),
+            start + complete as u64,
+        ))
+    }
+}
+
+impl<T> GenericInputData<T> for FileInputData
+where
+    T: From<String>,
+{
+    fn read(&self) -> Result<T, MapReduceError> {
+        log::debug!("reading {}", self.file_path.display());
+        let bytes = match self.buffer_size {
+            // `read_to_end` would skip the buffer for anything larger than it,
+            // so the file is read one buffer at a time instead.
+            Some(_) => {
+                let mut reader = self.open()?;
+                let mut bytes = Vec::new();
+                loop {
+                    let chunk = reader.fill_buf()?;
+                    if chunk.is_empty() {
+                        break bytes;
+                    }
+                    bytes.extend_from_slice(chunk);
+                    let read = chunk.len();
+                    reader.consume(read);
+                }
+            }
+            None => fs::read(&self.file_path)?,
+        };
+        Ok(T::from(decode_file_contents(&self.file_path, bytes)?))
+    }
+
+    fn path(&self) -> Option<&Path> {
+        Some(&self.file_path)
+    }
+
+    fn read_lines(&self) -> Result<LineIter<'_>, MapReduceError>
+    where
+        T: AsRef<str>,
+    {
+        log::debug!("streaming {}", self.file_path.display());
+        let file = self.open()?;
+        let mut reader: Box<dyn BufRead> = if is_gzip(&self.file_path) {
+            Box::new(BufReader::new(GzDecoder::new(file)))
+        } else {
+            Box::new(file)
+        };
+
+        // UTF-16 cannot be split on newline bytes, so decode it up front instead.
+        if utf16_encoding(reader.fill_buf()?).is_some() {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes)?;
+            let lines: Vec<_> = decode_text(&bytes)
+                .lines()
+                .map(|line| Ok(line.to_owned()))
+                .collect();
+            return Ok(Box::new(lines.into_iter()));
+        }
+
+        Ok(Box::new(decode_lines(reader)))
+    }
+}
+
+// Splits on raw bytes rather than using `BufRead::lines` so that invalid UTF-8
+// is replaced the same way `read` does instead of failing the whole input.
+fn decode_lines(reader: impl BufRead) -> impl Iterator<Item = io::Result<String>> {
+    reader.split(b'\n').map(|line| {
+        let mut line = line?;
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+        Ok(String::from_utf8_lossy(&line).into_owned())
+    })
+}
+
+pub(crate) fn is_gzip(file_path: &Path) -> bool {
+    file_path
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("gz"))
+}
+
+pub(crate) fn decode_file_contents(
+    file_path: &Path,
+    bytes: Vec<u8>,
+) -> Result<String, MapReduceError> {
+    let bytes = if is_gzip(file_path) {
+        let mut decompressed = Vec::new();
+        GzDecoder::new(bytes.as_slice()).read_to_end(&mut decompressed)?;
+        decompressed
+    } else {
+        bytes
+    };
+    Ok(decode_text(&bytes))
+}
+
+pub(crate) fn utf16_encoding(bytes: &[u8]) -> Option<(&'static Encoding, usize)> {
+    Encoding::for_bom(bytes).filter(|(encoding, _)| *encoding == UTF_16LE || *encoding == UTF_16BE)
+}
+
+// Only UTF-16 is detected, and only by its BOM; everything else is treated as
+// UTF-8 with invalid sequences replaced.
+pub(crate) fn decode_text(bytes: &[u8]) -> String {
+    match utf16_encoding(bytes) {
+        Some((encoding, bom_length)) => encoding
+            .decode_without_bom_handling(&bytes[bom_length..])
+            .0
+            .into_owned(),
+        None => String::from_utf8_lossy(bytes).into_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{write::GzEncoder, Compression};
+
+    use super::*;
+    use crate::{
+        create_workers, generate_inputs, run_map_reduce, test_util::TempDir, LineCountWorker,
+        RunOptions,
+    };
+
+    fn gzipped(content: &str) -> Vec<u8> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(content.as_bytes()).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn gzipped_files_are_decompressed() {
+        let dir = TempDir::new();
+        let input = FileInputData::new(dir.write("logs.txt.gz", gzipped("one\ntwo\nthree\n")));
+
+        let content: String = input.read().unwrap();
+        assert_eq!(content, "one\ntwo\nthree\n");
+        assert_eq!(GenericInputData::<String>::count_lines(&input).unwrap(), 3);
+    }
+
+    #[test]
+    fn files_without_a_gz_extension_are_read_as_is() {
+        let dir = TempDir::new();
+        let input = FileInputData::new(dir.write("plain.txt", "one\ntwo\n"));
+        assert_eq!(GenericInputData::<String>::count_lines(&input).unwrap(), 2);
+    }
+
+    #[test]
+    fn invalid_utf8_is_replaced_instead_of_failing() {
+        let dir = TempDir::new();
+        dir.write("garbled.log", b"ok\nbad \xff\xfe byte\nfine\n");
+        dir.write("clean.log", "one\n");
+
+        let workers = create_workers(generate_inputs(dir.path()).unwrap(), LineCountWorker::new);
+        let summary = run_map_reduce(workers, RunOptions::new()).unwrap();
+        assert_eq!((summary.result, summary.inputs_failed), (4, 0));
+
+        let input = FileInputData::new(dir.path().join("garbled.log"));
+        let content: String = input.read().unwrap();
+        assert_eq!(content, "ok\nbad \u{fffd}\u{fffd} byte\nfine\n");
+    }
+
+    #[test]
+    fn streamed_lines_match_buffered_lines() {
+        let dir = TempDir::new();
+        let content = "first\r\nsecond\n\nfourth\nno newline at the end";
+        let input = FileInputData::with_buffer_size(dir.write("lines.txt", content), 4);
+
+        let streamed: Vec<String> = GenericInputData::<String>::read_lines(&input)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        let buffered: String = input.read().unwrap();
+        assert_eq!(streamed, buffered.lines().collect::<Vec<_>>());
+        assert_eq!(GenericInputData::<String>::count_lines(&input).unwrap(), 5);
+    }
+
+    fn utf16le(content: &str) -> Vec<u8> {
+        let mut bytes = vec![0xff, 0xfe];
+        bytes.extend(content.encode_utf16().flat_map(u16::to_le_bytes));
+        bytes
+    }
+
+    #[test]
+    fn utf16_files_with_a_bom_are_decoded() {
+        let dir = TempDir::new();
+        let input = FileInputData::new(dir.write("wide.txt", utf16le("héllo\r\nwörld\r\nend\r\n")));
+
+        let content: String = input.read().unwrap();
+        assert_eq!(content, "héllo\r\nwörld\r\nend\r\n");
+        assert_eq!(GenericInputData::<String>::count_lines(&input).unwrap(), 3);
+    }
+
+    #[test]
+    fn any_buffer_size_reads_the_same_content() {
+        let dir = TempDir::new();
+        let content = "a line that is longer than the small buffers\n".repeat(50);
+        let path = dir.write("lines.txt", &content);
+        let gz_path = dir.write("lines.txt.gz", gzipped(&content));
+
+        for buffer_size in [1, 7, 64, 64 * 1024] {
+            for path in [&path, &gz_path] {
+                let input = FileInputData::with_buffer_size(path.clone(), buffer_size);
+                let read: String = input.read().unwrap();
+                assert_eq!(read, content, "buffer size {buffer_size}");
+            }
+        }
+    }
+
+    #[test]
+    fn read_since_returns_complete_lines_after_the_offset() {
+        let dir = TempDir::new();
+        let path = dir.write("app.log", "one\ntwo\nthr");
+        let input = FileInputData::new(path.clone());
+
+        assert_eq!(input.read_since(0).unwrap(), ("one\ntwo\n".to_string(), 8));
+        assert_eq!(input.read_since(4).unwrap(), ("two\n".to_string(), 8));
+        assert_eq!(input.read_since(8).unwrap(), (String::new(), 8));
+
+        fs::write(&path, "new\n").unwrap();
+        assert_eq!(input.read_since(8).unwrap(), ("new\n".to_string(), 4));
+
+        let gz = FileInputData::new(dir.write("app.log.gz", gzipped("one\n")));
+        assert!(matches!(
+            gz.read_since(0),
+            Err(MapReduceError::NotByteAddressable(_))
+        ));
+    }
+}
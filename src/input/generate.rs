@@ -0,0 +1,348 @@
+use std::{
+    fs::{self, DirEntry, Metadata},
+    path::{Path, PathBuf},
+};
+
+use super::{BinaryFileInputData, FileInputData, GenericInputData};
+use crate::MapReduceError;
+
+pub fn generate_inputs<T>(
+    data_dir: impl AsRef<Path>,
+) -> Result<Vec<Box<dyn GenericInputData<T>>>, MapReduceError>
+where
+    T: From<String>,
+{
+    generate_inputs_recursive(data_dir, false)
+}
+
+pub fn generate_inputs_recursive<T>(
+    data_dir: impl AsRef<Path>,
+    recursive: bool,
+) -> Result<Vec<Box<dyn GenericInputData<T>>>, MapReduceError>
+where
+    T: From<String>,
+{
+    collect_inputs(data_dir, recursive, |_| true)
+}
+
+pub fn generate_inputs_multi<T>(
+    data_dirs: &[impl AsRef<Path>],
+) -> Result<Vec<Box<dyn GenericInputData<T>>>, MapReduceError>
+where
+    T: From<String>,
+{
+    let mut inputs = Vec::new();
+    for data_dir in data_dirs {
+        inputs.extend(generate_inputs(data_dir)?);
+    }
+    Ok(inputs)
+}
+
+pub fn generate_inputs_filtered<T>(
+    data_dir: impl AsRef<Path>,
+    pattern: &str,
+) -> Result<Vec<Box<dyn GenericInputData<T>>>, MapReduceError>
+where
+    T: From<String>,
+{
+    collect_inputs(data_dir, false, |entry| {
+        entry
+            .file_name()
+            .to_str()
+            .is_some_and(|name| matches_pattern(name, pattern))
+    })
+}
+
+pub fn generate_inputs_by_metadata<T, P>(
+    data_dir: impl AsRef<Path>,
+    recursive: bool,
+    mut predicate: P,
+) -> Result<Vec<Box<dyn GenericInputData<T>>>, MapReduceError>
+where
+    T: From<String>,
+    P: FnMut(&Metadata) -> bool,
+{
+    collect_inputs(data_dir, recursive, |entry| {
+        entry.metadata().is_ok_and(|metadata| predicate(&metadata))
+    })
+}
+
+pub fn generate_inputs_binary(
+    data_dir: impl AsRef<Path>,
+) -> Result<Vec<Box<dyn GenericInputData<Vec<u8>>>>, MapReduceError> {
+    let inputs = collect_paths(data_dir.as_ref(), false, |_| true)?
+        .into_iter()
+        .map(|file_path| {
+            Box::new(BinaryFileInputData::new(file_path)) as Box<dyn GenericInputData<Vec<u8>>>
+        })
+        .collect();
+    Ok(inputs)
+}
+
+pub fn generate_inputs_from_manifest<T>(
+    manifest_path: impl AsRef<Path>,
+) -> Result<Vec<Box<dyn GenericInputData<T>>>, MapReduceError>
+where
+    T: From<String>,
+{
+    let manifest_path = manifest_path.as_ref();
+    let manifest = fs::read_to_string(manifest_path)?;
+    // Relative entries are resolved against the manifest, not the working directory.
+    let base_dir = manifest_path.parent().unwrap_or(Path::new(""));
+
+    let paths: Vec<PathBuf> = manifest
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .map(|line| base_dir.join(line))
+        .collect();
+
+    let missing: Vec<PathBuf> = paths
+        .iter()
+        .filter(|file_path| !file_path.is_file())
+        .cloned()
+        .collect();
+    if !missing.is_empty() {
+        return Err(MapReduceError::MissingInputs(missing));
+    }
+    log::info!(
+        "found {} inputs in {}",
+        paths.len(),
+        manifest_path.display()
+    );
+
+    Ok(paths
+        .into_iter()
+        .map(|file_path| Box::new(FileInputData::new(file_path)) as Box<dyn GenericInputData<T>>)
+        .collect())
+}
+
+fn matches_pattern(name: &str, pattern: &str) -> bool {
+    let name: Vec<char> = name.chars().collect();
+    let pattern: Vec<char> = pattern.chars().collect();
+
+    let (mut n, mut p) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                backtrack = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                n += 1;
+                p += 1;
+            }
+            _ => match backtrack {
+                Some((star, matched)) => {
+                    p = star + 1;
+                    n = matched + 1;
+                    backtrack = Some((star, n));
+                }
+                None => return false,
+            },
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+fn collect_inputs<T, F>(
+    data_dir: impl AsRef<Path>,
+    recursive: bool,
+    include: F,
+) -> Result<Vec<Box<dyn GenericInputData<T>>>, MapReduceError>
+where
+    T: From<String>,
+    F: FnMut(&DirEntry) -> bool,
+{
+    let inputs = collect_paths(data_dir.as_ref(), recursive, include)?
+        .into_iter()
+        .map(|file_path| Box::new(FileInputData::new(file_path)) as Box<dyn GenericInputData<T>>)
+        .collect();
+    Ok(inputs)
+}
+
+fn collect_paths<F>(
+    path: &Path,
+    recursive: bool,
+    mut include: F,
+) -> Result<Vec<PathBuf>, MapReduceError>
+where
+    F: FnMut(&DirEntry) -> bool,
+{
+    if !path.is_dir() {
+        return Err(MapReduceError::DirectoryNotFound(path.to_path_buf()));
+    }
+
+    let mut paths = Vec::new();
+    let mut pending = vec![path.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        for entry in fs::read_dir(&dir)?.flatten() {
+            let file_path = entry.path();
+            // `DirEntry::file_type` does not traverse symlinks, so linked
+            // directories are never descended into and cannot form cycles.
+            if recursive && entry.file_type().is_ok_and(|t| t.is_dir()) {
+                pending.push(file_path);
+                continue;
+            }
+            // Subdirectories and other special entries cannot be read as inputs.
+            if !file_path.is_file() {
+                continue;
+            }
+            if include(&entry) {
+                paths.push(file_path);
+            }
+        }
+    }
+
+    // `read_dir` yields entries in an OS-dependent order.
+    paths.sort();
+    log::info!("found {} inputs in {}", paths.len(), path.display());
+    Ok(paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        aggregate, create_workers, run_map_reduce, test_util::TempDir, LineCountWorker, RunOptions,
+    };
+
+    fn paths(inputs: &[Box<dyn GenericInputData<String>>]) -> Vec<PathBuf> {
+        inputs
+            .iter()
+            .map(|input| input.path().unwrap().to_path_buf())
+            .collect()
+    }
+
+    #[test]
+    fn recursion_descends_into_subdirectories_but_not_linked_ones() {
+        let dir = TempDir::new();
+        let top = dir.write("top.txt", "a");
+        let nested = dir.write("2024/01/nested.txt", "b");
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(dir.path().join("2024"), dir.path().join("link")).unwrap();
+
+        assert_eq!(
+            paths(&generate_inputs(dir.path()).unwrap()),
+            vec![top.clone()]
+        );
+        let recursive = generate_inputs_recursive(dir.path(), true).unwrap();
+        assert_eq!(paths(&recursive), [nested, top]);
+    }
+
+    #[test]
+    fn pattern_only_keeps_matching_files() {
+        let dir = TempDir::new();
+        dir.write("a.txt", "one\ntwo");
+        dir.write("b.txt", "three");
+        dir.write("image.png", [0x89, b'P', b'N', b'G', 0xff]);
+        dir.write("notes.txt.bak", "four");
+
+        let inputs = generate_inputs_filtered(dir.path(), "*.txt").unwrap();
+        let workers = create_workers(inputs, LineCountWorker::new);
+        assert_eq!(aggregate(workers).unwrap(), 3);
+        assert!(matches_pattern("data-01.csv", "data-??.csv"));
+        assert!(!matches_pattern("data-1.csv", "data-??.csv"));
+    }
+
+    #[test]
+    fn inputs_come_back_sorted() {
+        let dir = TempDir::new();
+        for name in ["c.txt", "a.txt", "b.txt"] {
+            dir.write(name, name);
+        }
+
+        let expected: Vec<PathBuf> = ["a.txt", "b.txt", "c.txt"]
+            .iter()
+            .map(|name| dir.path().join(name))
+            .collect();
+        assert_eq!(paths(&generate_inputs(dir.path()).unwrap()), expected);
+    }
+
+    #[test]
+    fn a_missing_or_non_directory_path_is_an_error() {
+        let dir = TempDir::new();
+        let missing = dir.path().join("typo");
+        let file = dir.write("file.txt", "not a directory");
+
+        for path in [missing, file] {
+            let error = generate_inputs::<String>(&path).err().unwrap();
+            assert!(
+                matches!(error, MapReduceError::DirectoryNotFound(reported) if reported == path)
+            );
+        }
+    }
+
+    #[test]
+    fn a_manifest_lists_inputs_and_reports_missing_ones() {
+        let dir = TempDir::new();
+        let first = dir.write("logs/a.txt", "one\ntwo");
+        let second = dir.write("logs/b.txt", "three");
+        let manifest = dir.write("inputs.txt", "# fixtures\n logs/a.txt \n\nlogs/b.txt\n");
+
+        let inputs = generate_inputs_from_manifest::<String>(&manifest).unwrap();
+        assert_eq!(paths(&inputs), vec![first, second]);
+
+        let manifest = dir.write("broken.txt", "logs/a.txt\nlogs/gone.txt\nlogs/b.txt\n");
+        let missing = dir.path().join("logs/gone.txt");
+        assert!(matches!(
+            generate_inputs_from_manifest::<String>(&manifest),
+            Err(MapReduceError::MissingInputs(reported)) if reported == vec![missing]
+        ));
+    }
+
+    #[test]
+    fn a_metadata_predicate_filters_by_size() {
+        let dir = TempDir::new();
+        dir.write("tiny.txt", "a\n");
+        let medium = dir.write("medium.txt", "line\n".repeat(20));
+        dir.write("huge.txt", "line\n".repeat(2000));
+
+        let inputs = generate_inputs_by_metadata(dir.path(), false, |metadata| {
+            (10..1000).contains(&metadata.len())
+        })
+        .unwrap();
+        assert_eq!(paths(&inputs), vec![medium]);
+        assert_eq!(
+            aggregate(create_workers(inputs, LineCountWorker::new)).unwrap(),
+            20
+        );
+    }
+
+    #[test]
+    fn multiple_dirs_are_concatenated() {
+        let dir = TempDir::new();
+        dir.write("logs/a.txt", "one\ntwo");
+        dir.write("tmp/b.txt", "three");
+        dir.write("tmp/c.txt", "four\nfive\nsix");
+
+        let dirs = [dir.path().join("logs"), dir.path().join("tmp")];
+        let inputs = generate_inputs_multi(&dirs).unwrap();
+        assert_eq!(inputs.len(), 3);
+        assert_eq!(
+            aggregate(create_workers(inputs, LineCountWorker::new)).unwrap(),
+            6
+        );
+    }
+
+    #[test]
+    fn subdirectories_are_skipped_without_recursion() {
+        let dir = TempDir::new();
+        dir.write("a.txt", "one\ntwo");
+        dir.write("b.txt", "three");
+        dir.write("nested/c.txt", "not counted");
+        fs::create_dir(dir.path().join("empty")).unwrap();
+
+        let inputs = generate_inputs(dir.path()).unwrap();
+        assert_eq!(inputs.len(), 2);
+        let summary = run_map_reduce(
+            create_workers(inputs, LineCountWorker::new),
+            RunOptions::new(),
+        )
+        .unwrap();
+        assert_eq!((summary.result, summary.inputs_failed), (3, 0));
+    }
+}
@@ -0,0 +1,84 @@
+use std::{
+    path::Path,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use super::GenericInputData;
+use crate::MapReduceError;
+
+pub struct HttpInputData {
+    url: String,
+    consumed: AtomicBool,
+}
+
+impl HttpInputData {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            consumed: AtomicBool::new(false),
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+}
+
+impl<T> GenericInputData<T> for HttpInputData
+where
+    T: From<String>,
+{
+    // The response may differ from one request to the next, so only the first
+    // successful read is allowed. A failed request does not count, which
+    // leaves `RetryingInputData` free to try again.
+    fn read(&self) -> Result<T, MapReduceError> {
+        if self.consumed.load(Ordering::SeqCst) {
+            return Err(MapReduceError::AlreadyConsumed);
+        }
+        // ureq reports non-2xx responses as `Error::StatusCode`.
+        let mut response = ureq::get(&self.url).call()?;
+        let content = response.body_mut().read_to_string()?;
+        if self.consumed.swap(true, Ordering::SeqCst) {
+            return Err(MapReduceError::AlreadyConsumed);
+        }
+        Ok(T::from(content))
+    }
+
+    fn path(&self) -> Option<&Path> {
+        Some(Path::new(&self.url))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{test_util::serve, LineCountWorker, Mapper, Reducer};
+
+    #[test]
+    fn counts_the_lines_of_a_served_body() {
+        let url = serve(vec![(200, "one\ntwo\nthree\n")]);
+        let mut worker = LineCountWorker::new(Arc::new(HttpInputData::new(url)));
+        worker.map().unwrap();
+        assert_eq!(worker.get_result(), 3);
+    }
+
+    #[test]
+    fn non_success_statuses_are_errors() {
+        let input = HttpInputData::new(serve(vec![(404, "missing")]));
+        let outcome: Result<String, _> = input.read();
+        assert!(matches!(outcome, Err(MapReduceError::Http(_))));
+    }
+
+    #[test]
+    fn only_a_successful_read_consumes_the_url() {
+        let input = HttpInputData::new(serve(vec![(503, "busy"), (200, "body\n")]));
+        let failed: Result<String, _> = input.read();
+        assert!(matches!(failed, Err(MapReduceError::Http(_))));
+        let body: String = input.read().unwrap();
+        assert_eq!(body, "body\n");
+        let again: Result<String, _> = input.read();
+        assert!(matches!(again, Err(MapReduceError::AlreadyConsumed)));
+    }
+}
@@ -0,0 +1,60 @@
+use parking_lot::Mutex;
+
+use super::GenericInputData;
+use crate::MapReduceError;
+
+pub struct IterInputData<I> {
+    items: Mutex<Option<I>>,
+}
+
+impl<I> IterInputData<I>
+where
+    I: Iterator<Item = String>,
+{
+    pub fn new(items: I) -> Self {
+        Self {
+            items: Mutex::new(Some(items)),
+        }
+    }
+}
+
+impl<T, I> GenericInputData<T> for IterInputData<I>
+where
+    T: From<String>,
+    I: Iterator<Item = String> + Send,
+{
+    // Each item becomes one line. Like a reader, the iterator is used up by the
+    // first read, so reading again is an error.
+    fn read(&self) -> Result<T, MapReduceError> {
+        let items = self
+            .items
+            .lock()
+            .take()
+            .ok_or(MapReduceError::AlreadyConsumed)?;
+        Ok(T::from(items.collect::<Vec<_>>().join("\n")))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{aggregate, create_workers, LineCountWorker};
+
+    #[test]
+    fn each_item_is_one_line() {
+        let input_list: Vec<Box<dyn GenericInputData<String>>> = vec![Box::new(
+            IterInputData::new((0..5).map(|index| index.to_string())),
+        )];
+        let workers = create_workers(input_list, LineCountWorker::new);
+        assert_eq!(aggregate(workers).unwrap(), 5);
+    }
+
+    #[test]
+    fn the_iterator_is_used_up_by_the_first_read() {
+        let input = IterInputData::new(["a", "b"].into_iter().map(String::from));
+        let first: String = input.read().unwrap();
+        assert_eq!(first, "a\nb");
+        let second: Result<String, _> = input.read();
+        assert!(matches!(second, Err(MapReduceError::AlreadyConsumed)));
+    }
+}
@@ -0,0 +1,172 @@
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+    thread,
+};
+
+use memmap2::Mmap;
+
+use super::{
+    file::{is_gzip, utf16_encoding},
+    GenericInputData,
+};
+use crate::MapReduceError;
+
+pub struct MmapFileInputData {
+    file_path: PathBuf,
+}
+
+impl MmapFileInputData {
+    pub fn new(file_path: PathBuf) -> Self {
+        Self { file_path }
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.file_path
+    }
+
+    fn map(&self) -> Result<Mmap, MapReduceError> {
+        map_file(&self.file_path)
+    }
+}
+
+fn map_file(file_path: &Path) -> Result<Mmap, MapReduceError> {
+    let file = File::open(file_path)?;
+    // SAFETY: the mapping is only read for the duration of a single call and
+    // inputs are expected not to be truncated while a job is running.
+    let mmap = unsafe { Mmap::map(&file)? };
+    // The mapped bytes are counted and decoded as they are, which only works
+    // for text that can be cut on any newline byte.
+    if is_gzip(file_path) || utf16_encoding(&mmap).is_some() {
+        return Err(MapReduceError::NotByteAddressable(file_path.to_path_buf()));
+    }
+    Ok(mmap)
+}
+
+impl<T> GenericInputData<T> for MmapFileInputData
+where
+    T: From<String>,
+{
+    fn read(&self) -> Result<T, MapReduceError> {
+        let mmap = self.map()?;
+        Ok(T::from(String::from_utf8_lossy(&mmap).into_owned()))
+    }
+
+    fn path(&self) -> Option<&Path> {
+        Some(&self.file_path)
+    }
+
+    fn count_lines(&self) -> Result<usize, MapReduceError>
+    where
+        T: AsRef<str>,
+    {
+        let mmap = self.map()?;
+        Ok(count_newlines(&mmap) + usize::from(is_unterminated(&mmap)))
+    }
+}
+
+/// Counts the lines of one file by scanning `threads` ranges of it at once.
+pub fn count_lines_parallel(
+    path: impl AsRef<Path>,
+    threads: usize,
+) -> Result<usize, MapReduceError> {
+    let mmap = map_file(path.as_ref())?;
+    let range_size = mmap.len().div_ceil(threads.max(1)).max(1);
+
+    // Each newline byte falls in exactly one range, so a range that starts or
+    // ends mid-line needs no adjustment; only the final unterminated line is
+    // counted separately.
+    let newlines = thread::scope(|scope| {
+        let counters: Vec<_> = mmap
+            .chunks(range_size)
+            .map(|range| scope.spawn(move || count_newlines(range)))
+            .collect();
+        counters
+            .into_iter()
+            .map(|counter| counter.join().expect("Line counting thread panicked"))
+            .sum::<usize>()
+    });
+    Ok(newlines + usize::from(is_unterminated(&mmap)))
+}
+
+fn count_newlines(bytes: &[u8]) -> usize {
+    bytes.iter().filter(|&&byte| byte == b'\n').count()
+}
+
+fn is_unterminated(bytes: &[u8]) -> bool {
+    bytes.last().is_some_and(|&byte| byte != b'\n')
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use flate2::{write::GzEncoder, Compression};
+
+    use super::*;
+    use crate::{test_util::TempDir, FileInputData};
+
+    fn multi_megabyte_file(dir: &TempDir) -> PathBuf {
+        let content: String = (0..100_000)
+            .map(|index| format!("line {index} of a generated file for counting\n"))
+            .collect();
+        assert!(content.len() > 4 * 1024 * 1024);
+        dir.write("big.txt", content + "unterminated")
+    }
+
+    #[test]
+    fn mmap_counts_match_buffered_counts() {
+        let dir = TempDir::new();
+        let path = multi_megabyte_file(&dir);
+
+        let mmap = MmapFileInputData::new(path.clone());
+        let buffered = FileInputData::new(path);
+        let mmap_lines = GenericInputData::<String>::count_lines(&mmap).unwrap();
+        assert_eq!(
+            mmap_lines,
+            GenericInputData::<String>::count_lines(&buffered).unwrap()
+        );
+        assert_eq!(mmap_lines, 100_001);
+    }
+
+    #[test]
+    fn parallel_counts_match_the_single_threaded_count() {
+        let dir = TempDir::new();
+        let path = multi_megabyte_file(&dir);
+        let buffered = FileInputData::new(path.clone());
+        let single = GenericInputData::<String>::count_lines(&buffered).unwrap();
+
+        for threads in [1, 2, 3, 8, 64] {
+            assert_eq!(count_lines_parallel(&path, threads).unwrap(), single);
+        }
+
+        // More threads than bytes leaves ranges of a single byte.
+        let short = dir.write("short.txt", "a\nb\nc");
+        assert_eq!(count_lines_parallel(&short, 16).unwrap(), 3);
+        let empty = dir.write("empty.txt", "");
+        assert_eq!(count_lines_parallel(&empty, 4).unwrap(), 0);
+    }
+
+    #[test]
+    fn compressed_and_utf16_files_are_rejected() {
+        let dir = TempDir::new();
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"one\ntwo\nthree\n").unwrap();
+        let gz = dir.write("lines.txt.gz", encoder.finish().unwrap());
+        let mut utf16 = vec![0xff, 0xfe];
+        utf16.extend("one\ntwo\n".encode_utf16().flat_map(u16::to_le_bytes));
+        let utf16 = dir.write("wide.txt", utf16);
+
+        for path in [gz, utf16] {
+            assert!(matches!(
+                count_lines_parallel(&path, 4),
+                Err(MapReduceError::NotByteAddressable(rejected)) if rejected == path
+            ));
+            let input = MmapFileInputData::new(path);
+            assert!(matches!(
+                GenericInputData::<String>::count_lines(&input),
+                Err(MapReduceError::NotByteAddressable(_))
+            ));
+        }
+    }
+}
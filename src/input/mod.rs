@@ -0,0 +1,72 @@
+mod archive;
+mod binary;
+mod cached;
+mod chunk;
+mod file;
+mod generate;
+mod http;
+mod iter;
+mod mmap;
+mod reader;
+mod retry;
+mod stdin;
+mod string;
+
+use std::{io, path::Path};
+
+use crate::MapReduceError;
+
+pub use archive::{generate_inputs_from_tar, generate_inputs_from_zip, ArchiveEntryInputData};
+pub use binary::BinaryFileInputData;
+pub use cached::CachedInputData;
+pub(crate) use cached::PrefetchedInputData;
+pub use chunk::{chunk_file, FileChunkInputData};
+#[cfg(feature = "async")]
+pub(crate) use file::decode_file_contents;
+pub use file::FileInputData;
+pub use generate::{
+    generate_inputs, generate_inputs_binary, generate_inputs_by_metadata, generate_inputs_filtered,
+    generate_inputs_from_manifest, generate_inputs_multi, generate_inputs_recursive,
+};
+pub use http::HttpInputData;
+pub use iter::IterInputData;
+pub use mmap::{count_lines_parallel, MmapFileInputData};
+pub use reader::ReaderInputData;
+pub use retry::RetryingInputData;
+pub use stdin::StdinInputData;
+pub use string::StringInputData;
+
+pub type LineIter<'a> = Box<dyn Iterator<Item = io::Result<String>> + 'a>;
+
+pub trait GenericInputData<T>: Send + Sync {
+    fn read(&self) -> Result<T, MapReduceError>;
+
+    fn path(&self) -> Option<&Path> {
+        None
+    }
+
+    fn read_lines(&self) -> Result<LineIter<'_>, MapReduceError>
+    where
+        T: AsRef<str>,
+    {
+        let content = self.read()?;
+        let lines: Vec<_> = content
+            .as_ref()
+            .lines()
+            .map(|line| Ok(line.to_owned()))
+            .collect();
+        Ok(Box::new(lines.into_iter()))
+    }
+
+    fn count_lines(&self) -> Result<usize, MapReduceError>
+    where
+        T: AsRef<str>,
+    {
+        let mut count = 0;
+        for line in self.read_lines()? {
+            line?;
+            count += 1;
+        }
+        Ok(count)
+    }
+}
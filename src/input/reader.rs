@@ -0,0 +1,93 @@
+use std::{
+    io::Read,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use parking_lot::Mutex;
+
+use super::{file::decode_text, GenericInputData};
+use crate::MapReduceError;
+
+pub struct ReaderInputData<R> {
+    reader: Mutex<R>,
+    consumed: AtomicBool,
+}
+
+impl<R: Read> ReaderInputData<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader: Mutex::new(reader),
+            consumed: AtomicBool::new(false),
+        }
+    }
+}
+
+impl<T, R> GenericInputData<T> for ReaderInputData<R>
+where
+    T: From<String>,
+    R: Read + Send,
+{
+    // A reader can only be consumed once, so a second read is an error rather
+    // than empty data. Wrap it in `CachedInputData` to read it more than once.
+    // As with `HttpInputData`, a failed read does not count.
+    fn read(&self) -> Result<T, MapReduceError> {
+        let mut reader = self.reader.lock();
+        if self.consumed.load(Ordering::SeqCst) {
+            return Err(MapReduceError::AlreadyConsumed);
+        }
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes)?;
+        self.consumed.store(true, Ordering::SeqCst);
+        // Decoded the same way as files, so one bad byte doesn't fail the run.
+        Ok(T::from(decode_text(&bytes)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Cursor};
+
+    use super::*;
+    use crate::{run, LineCountWorker};
+
+    struct FailOnce {
+        failed: bool,
+        inner: Cursor<&'static str>,
+    }
+
+    impl Read for FailOnce {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if !self.failed {
+                self.failed = true;
+                return Err(io::Error::new(io::ErrorKind::ConnectionReset, "dropped"));
+            }
+            self.inner.read(buf)
+        }
+    }
+
+    #[test]
+    fn counts_lines_from_any_reader() {
+        let reader = ReaderInputData::new(Cursor::new("a\nb\n"));
+        let input_list: Vec<Box<dyn GenericInputData<String>>> = vec![Box::new(reader)];
+        assert_eq!(run(input_list, LineCountWorker::new).unwrap(), 2);
+    }
+
+    #[test]
+    fn invalid_utf8_is_replaced() {
+        let reader = ReaderInputData::new(Cursor::new(b"ok \xff\n".to_vec()));
+        let content: String = reader.read().unwrap();
+        assert_eq!(content, "ok \u{fffd}\n");
+    }
+
+    #[test]
+    fn a_failed_read_does_not_consume_the_reader() {
+        let reader = ReaderInputData::new(FailOnce {
+            failed: false,
+            inner: Cursor::new("after\n"),
+        });
+        let first: Result<String, _> = reader.read();
+        assert!(matches!(first, Err(MapReduceError::Io(_))));
+        let second: String = reader.read().unwrap();
+        assert_eq!(second, "after\n");
+    }
+}
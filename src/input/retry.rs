@@ -0,0 +1,128 @@
+use std::{io, path::Path, thread, time::Duration};
+
+use super::GenericInputData;
+use crate::MapReduceError;
+
+pub struct RetryingInputData<I> {
+    input_data: I,
+    max_retries: u32,
+    backoff: Duration,
+}
+
+impl<I> RetryingInputData<I> {
+    pub fn new(input_data: I, max_retries: u32, backoff: Duration) -> Self {
+        Self {
+            input_data,
+            max_retries,
+            backoff,
+        }
+    }
+}
+
+impl<T, I> GenericInputData<T> for RetryingInputData<I>
+where
+    I: GenericInputData<T>,
+{
+    fn read(&self) -> Result<T, MapReduceError> {
+        let mut delay = self.backoff;
+        let mut attempt = 0;
+        loop {
+            match self.input_data.read() {
+                Ok(data) => return Ok(data),
+                Err(error) if attempt < self.max_retries && is_transient(&error) => {
+                    attempt += 1;
+                    log::warn!("read failed ({error}), retrying in {delay:?}");
+                    thread::sleep(delay);
+                    delay = delay.saturating_mul(2);
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    fn path(&self) -> Option<&Path> {
+        self.input_data.path()
+    }
+}
+
+// Retrying only helps when the next attempt could succeed; a missing file, a
+// consumed reader or a 4xx response will fail the same way every time.
+fn is_transient(error: &MapReduceError) -> bool {
+    match error {
+        MapReduceError::Io(error) => matches!(
+            error.kind(),
+            io::ErrorKind::Interrupted | io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock
+        ),
+        MapReduceError::Http(error) => match error.as_ref() {
+            ureq::Error::StatusCode(status) => *status >= 500,
+            ureq::Error::Io(_)
+            | ureq::Error::Timeout(_)
+            | ureq::Error::ConnectionFailed
+            | ureq::Error::HostNotFound
+            | ureq::Error::BodyStalled => true,
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+    use crate::{test_util::serve, HttpInputData};
+
+    struct FlakyInput {
+        failures: u32,
+        attempts: AtomicU32,
+    }
+
+    impl GenericInputData<String> for FlakyInput {
+        fn read(&self) -> Result<String, MapReduceError> {
+            let attempt = self.attempts.fetch_add(1, Ordering::SeqCst) + 1;
+            if attempt <= self.failures {
+                return Err(io::Error::from(io::ErrorKind::TimedOut).into());
+            }
+            Ok("finally".to_string())
+        }
+    }
+
+    fn flaky(failures: u32, max_retries: u32) -> RetryingInputData<FlakyInput> {
+        let input = FlakyInput {
+            failures,
+            attempts: AtomicU32::new(0),
+        };
+        RetryingInputData::new(input, max_retries, Duration::from_millis(1))
+    }
+
+    #[test]
+    fn succeeds_on_the_third_attempt() {
+        let retrying = flaky(2, 3);
+        assert_eq!(retrying.read().unwrap(), "finally");
+        assert_eq!(retrying.input_data.attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn gives_up_with_the_last_error() {
+        let retrying = flaky(5, 2);
+        let result: Result<String, _> = retrying.read();
+        assert!(
+            matches!(result, Err(MapReduceError::Io(error)) if error.kind() == io::ErrorKind::TimedOut)
+        );
+        assert_eq!(retrying.input_data.attempts.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn only_transient_http_errors_are_retried() {
+        let url = serve(vec![(500, "busy"), (200, "ok\n")]);
+        let retrying = RetryingInputData::new(HttpInputData::new(url), 2, Duration::from_millis(1));
+        let content: String = retrying.read().unwrap();
+        assert_eq!(content, "ok\n");
+
+        let url = serve(vec![(404, "missing"), (200, "ok\n")]);
+        let retrying = RetryingInputData::new(HttpInputData::new(url), 2, Duration::from_millis(1));
+        let result: Result<String, _> = retrying.read();
+        assert!(matches!(result, Err(MapReduceError::Http(_))));
+    }
+}
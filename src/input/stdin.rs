@@ -0,0 +1,62 @@
+use std::io::{self, Read};
+
+use super::{GenericInputData, ReaderInputData};
+use crate::MapReduceError;
+
+pub struct StdinInputData<R = io::Stdin> {
+    reader: ReaderInputData<R>,
+}
+
+impl StdinInputData {
+    pub fn new() -> Self {
+        Self::from_reader(io::stdin())
+    }
+}
+
+impl Default for StdinInputData {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R: Read> StdinInputData<R> {
+    pub fn from_reader(reader: R) -> Self {
+        Self {
+            reader: ReaderInputData::new(reader),
+        }
+    }
+}
+
+impl<T, R> GenericInputData<T> for StdinInputData<R>
+where
+    T: From<String>,
+    R: Read + Send,
+{
+    fn read(&self) -> Result<T, MapReduceError> {
+        self.reader.read()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+    use crate::{run, LineCountWorker};
+
+    #[test]
+    fn counts_lines_from_a_simulated_stdin() {
+        let stdin = StdinInputData::from_reader(Cursor::new("one\ntwo\nthree\n"));
+        let input_list: Vec<Box<dyn GenericInputData<String>>> = vec![Box::new(stdin)];
+        assert_eq!(run(input_list, LineCountWorker::new).unwrap(), 3);
+    }
+
+    #[test]
+    fn stdin_can_only_be_read_once() {
+        let stdin = StdinInputData::from_reader(Cursor::new("piped"));
+        let first: String = stdin.read().unwrap();
+        assert_eq!(first, "piped");
+        let second: Result<String, _> = stdin.read();
+        assert!(matches!(second, Err(MapReduceError::AlreadyConsumed)));
+    }
+}
@@ -0,0 +1,39 @@
+use super::GenericInputData;
+use crate::MapReduceError;
+
+#[derive(Default)]
+pub struct StringInputData {
+    content: String,
+}
+
+impl StringInputData {
+    pub fn new(content: impl Into<String>) -> Self {
+        Self {
+            content: content.into(),
+        }
+    }
+}
+
+impl<T> GenericInputData<T> for StringInputData
+where
+    T: From<String>,
+{
+    fn read(&self) -> Result<T, MapReduceError> {
+        Ok(T::from(self.content.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{LineCountWorker, Mapper, Reducer};
+
+    #[test]
+    fn line_counts_an_in_memory_string() {
+        let mut worker = LineCountWorker::new(Arc::new(StringInputData::new("a\nb\nc")));
+        worker.map().unwrap();
+        assert_eq!(worker.get_result(), 3);
+    }
+}
@@ -0,0 +1,576 @@
+use std::{
+    fmt,
+    fs::{self, Metadata},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{atomic::AtomicBool, Arc},
+};
+
+use clap::{builder::PossibleValue, ValueEnum};
+use parking_lot::Mutex;
+use regex::Regex;
+
+use crate::{
+    create_workers, create_workers_deduped, generate_inputs_by_metadata, generate_inputs_filtered,
+    generate_inputs_recursive, run_map_reduce_inspecting, sample_inputs, ByteSizeWorker,
+    CharCountWorker, CountMode, GenericInputData, LineCountWorker, MapReduceError, MapReducer,
+    NonBlankLineCountWorker, RegexMatchCountWorker, RunOptions, ThreadPool, WordCountWorker,
+};
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum WorkerKind {
+    #[default]
+    Lines,
+    Words,
+    Chars,
+    Bytes,
+    NonBlankLines,
+    AverageLineLength,
+    Metrics,
+    LineLengthPercentiles,
+    LongestLine,
+    DuplicateLines,
+    DistinctLines,
+    ApproxDistinctLines,
+    Sum,
+    ApproxTopWords,
+    WordFrequency,
+    CharFrequency,
+    JsonRecords,
+    RegexMatches(String),
+}
+
+// Every worker that can be picked by name, in the order they are listed on
+// the command line.
+const NAMED_WORKERS: &[WorkerKind] = &[
+    WorkerKind::Lines,
+    WorkerKind::Words,
+    WorkerKind::Chars,
+    WorkerKind::Bytes,
+    WorkerKind::NonBlankLines,
+    WorkerKind::AverageLineLength,
+    WorkerKind::Metrics,
+    WorkerKind::LineLengthPercentiles,
+    WorkerKind::LongestLine,
+    WorkerKind::DuplicateLines,
+    WorkerKind::DistinctLines,
+    WorkerKind::ApproxDistinctLines,
+    WorkerKind::Sum,
+    WorkerKind::ApproxTopWords,
+    WorkerKind::WordFrequency,
+    WorkerKind::CharFrequency,
+    WorkerKind::JsonRecords,
+];
+
+impl WorkerKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Lines => "lines",
+            Self::Words => "words",
+            Self::Chars => "chars",
+            Self::Bytes => "bytes",
+            Self::NonBlankLines => "non-blank-lines",
+            Self::AverageLineLength => "avg-line-length",
+            Self::Metrics => "metrics",
+            Self::LineLengthPercentiles => "line-length-percentiles",
+            Self::LongestLine => "longest-line",
+            Self::DuplicateLines => "duplicate-lines",
+            Self::DistinctLines => "distinct-lines",
+            Self::ApproxDistinctLines => "approx-distinct-lines",
+            Self::Sum => "sum",
+            Self::ApproxTopWords => "approx-top-words",
+            Self::WordFrequency => "word-frequency",
+            Self::CharFrequency => "char-frequency",
+            Self::JsonRecords => "json-records",
+            Self::RegexMatches(_) => "regex-matches",
+        }
+    }
+
+    /// What the worker's result is called when it is printed.
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Lines => "Lines",
+            Self::Words => "Words",
+            Self::Chars => "Chars",
+            Self::Bytes => "Bytes",
+            Self::NonBlankLines => "Non-blank lines",
+            Self::AverageLineLength => "Average line length",
+            Self::Metrics => "Metrics",
+            Self::LineLengthPercentiles => "Line length percentiles",
+            Self::LongestLine => "Longest line",
+            Self::DuplicateLines => "Duplicate lines",
+            Self::DistinctLines => "Distinct lines",
+            Self::ApproxDistinctLines => "Approximate distinct lines",
+            Self::Sum => "Sum",
+            Self::ApproxTopWords => "Approximate top words",
+            Self::WordFrequency => "Word frequency",
+            Self::CharFrequency => "Characters",
+            Self::JsonRecords => "JSON records",
+            Self::RegexMatches(_) => "Matches",
+        }
+    }
+
+    /// Whether the worker's result is a plain count of inputs' contents, which
+    /// is what [`MapReduceJob`] runs and what per-input results can be
+    /// written and sampled for.
+    pub fn is_count(&self) -> bool {
+        matches!(
+            self,
+            Self::Lines
+                | Self::Words
+                | Self::Chars
+                | Self::Bytes
+                | Self::NonBlankLines
+                | Self::RegexMatches(_)
+        )
+    }
+}
+
+impl fmt::Display for WorkerKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+// `MapReducer` is not object safe, so workers are picked at runtime through
+// `WorkerKind` rather than as trait objects.
+impl FromStr for WorkerKind {
+    type Err = MapReduceError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        NAMED_WORKERS
+            .iter()
+            .find(|kind| kind.name() == name)
+            .cloned()
+            .ok_or_else(|| MapReduceError::UnknownWorker(name.to_string()))
+    }
+}
+
+// A regex worker needs a pattern, so it can be built but not named.
+impl ValueEnum for WorkerKind {
+    fn value_variants<'a>() -> &'a [Self] {
+        NAMED_WORKERS
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        match self {
+            Self::RegexMatches(_) => None,
+            kind => Some(PossibleValue::new(kind.name())),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SkipEmpty(pub bool);
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct JobOutcome {
+    pub result: usize,
+    pub skipped: Vec<PathBuf>,
+    pub inputs_processed: usize,
+    pub inputs_failed: usize,
+    /// Inputs left unmapped because the run was cancelled.
+    pub cancelled: usize,
+}
+
+#[derive(Clone)]
+struct MetadataFilter(Arc<dyn Fn(&Metadata) -> bool + Send + Sync>);
+
+impl fmt::Debug for MetadataFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("MetadataFilter(..)")
+    }
+}
+
+type ResultCallback = dyn FnMut(&Path, usize) + Send;
+
+#[derive(Clone)]
+struct ResultHook(Arc<Mutex<ResultCallback>>);
+
+impl fmt::Debug for ResultHook {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ResultHook(..)")
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MapReduceJob {
+    data_dirs: Vec<PathBuf>,
+    recursive: bool,
+    pattern: Option<String>,
+    threads: Option<usize>,
+    worker: WorkerKind,
+    skip_empty: SkipEmpty,
+    dedup: bool,
+    limit: Option<usize>,
+    sample: Option<(f64, u64)>,
+    ordered: bool,
+    metadata_filter: Option<MetadataFilter>,
+    on_result: Option<ResultHook>,
+    cancel: Option<Arc<AtomicBool>>,
+}
+
+impl MapReduceJob {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a directory to read inputs from; inputs from each directory follow
+    /// those of the directories added before it.
+    pub fn data_dir(mut self, data_dir: impl Into<PathBuf>) -> Self {
+        self.data_dirs.push(data_dir.into());
+        self
+    }
+
+    pub fn recursive(mut self, recursive: bool) -> Self {
+        self.recursive = recursive;
+        self
+    }
+
+    /// Only reads files directly in each directory whose names match this
+    /// glob, as [`generate_inputs_filtered`] does. Takes precedence over
+    /// `recursive` and `filter_metadata`.
+    pub fn pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.pattern = Some(pattern.into());
+        self
+    }
+
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = Some(threads);
+        self
+    }
+
+    pub fn worker(mut self, worker: WorkerKind) -> Self {
+        self.worker = worker;
+        self
+    }
+
+    pub fn skip_empty(mut self, skip_empty: SkipEmpty) -> Self {
+        self.skip_empty = skip_empty;
+        self
+    }
+
+    pub fn dedup(mut self, dedup: bool) -> Self {
+        self.dedup = dedup;
+        self
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Only reads this fraction of the inputs, picked with `seed` after the
+    /// limit is applied, as [`sample_inputs`] does.
+    pub fn sample(mut self, fraction: f64, seed: u64) -> Self {
+        self.sample = Some((fraction, seed));
+        self
+    }
+
+    /// See [`RunOptions::ordered`].
+    pub fn ordered(mut self, ordered: bool) -> Self {
+        self.ordered = ordered;
+        self
+    }
+
+    pub fn filter_metadata(
+        mut self,
+        predicate: impl Fn(&Metadata) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.metadata_filter = Some(MetadataFilter(Arc::new(predicate)));
+        self
+    }
+
+    /// Called with the path and result of each successfully mapped input as it
+    /// is reduced, exactly once per input.
+    pub fn on_result(mut self, callback: impl FnMut(&Path, usize) + Send + 'static) -> Self {
+        self.on_result = Some(ResultHook(Arc::new(Mutex::new(callback))));
+        self
+    }
+
+    /// See [`RunOptions::cancel_flag`].
+    pub fn cancel_flag(mut self, cancel: Arc<AtomicBool>) -> Self {
+        self.cancel = Some(cancel);
+        self
+    }
+
+    pub fn run(&self) -> Result<usize, MapReduceError> {
+        self.run_detailed().map(|outcome| outcome.result)
+    }
+
+    pub fn run_detailed(&self) -> Result<JobOutcome, MapReduceError> {
+        match &self.worker {
+            WorkerKind::Lines => self.execute(LineCountWorker::new),
+            WorkerKind::Words => self.execute(WordCountWorker::new),
+            WorkerKind::Chars => {
+                self.execute(|input| CharCountWorker::new(input, CountMode::Chars))
+            }
+            WorkerKind::Bytes => self.execute(ByteSizeWorker::new),
+            WorkerKind::NonBlankLines => self.execute(NonBlankLineCountWorker::new),
+            WorkerKind::RegexMatches(pattern) => {
+                let pattern = Regex::new(pattern)?;
+                self.execute(|input| RegexMatchCountWorker::new(input, pattern.clone()))
+            }
+            kind => Err(MapReduceError::NotACount(kind.name())),
+        }
+    }
+
+    pub fn thread_pool(&self) -> Result<ThreadPool, MapReduceError> {
+        match self.threads {
+            Some(0) => Err(MapReduceError::InvalidThreadCount),
+            Some(threads) => Ok(ThreadPool::new(threads)),
+            None => Ok(ThreadPool::default()),
+        }
+    }
+
+    // The inputs a run would read, in order, after empty files are skipped and
+    // the limit and sample are applied. Skipped paths are added to `skipped`.
+    fn collect_inputs(
+        &self,
+        skipped: &mut Vec<PathBuf>,
+    ) -> Result<Vec<Box<dyn GenericInputData<String>>>, MapReduceError> {
+        if self.data_dirs.is_empty() {
+            return Err(MapReduceError::MissingDataDir);
+        }
+
+        let mut input_list = Vec::new();
+        for data_dir in &self.data_dirs {
+            let inputs = match (&self.pattern, &self.metadata_filter) {
+                (Some(pattern), _) => generate_inputs_filtered(data_dir, pattern)?,
+                (None, Some(MetadataFilter(predicate))) => {
+                    generate_inputs_by_metadata(data_dir, self.recursive, |metadata| {
+                        predicate(metadata)
+                    })?
+                }
+                (None, None) => generate_inputs_recursive(data_dir, self.recursive)?,
+            };
+            input_list.extend(inputs);
+        }
+        if input_list.is_empty() {
+            return Err(MapReduceError::EmptyInput);
+        }
+
+        if self.skip_empty.0 {
+            input_list.retain(|input| match input.path() {
+                Some(path) if fs::metadata(path).is_ok_and(|metadata| metadata.len() == 0) => {
+                    skipped.push(path.to_path_buf());
+                    false
+                }
+                _ => true,
+            });
+        }
+
+        // Inputs are already sorted, so the same files are kept on every run.
+        if let Some(limit) = self.limit {
+            input_list.truncate(limit);
+        }
+        match self.sample {
+            Some((fraction, seed)) => sample_inputs(input_list, fraction, seed),
+            None => Ok(input_list),
+        }
+    }
+
+    /// The inputs a run would read, so that other workers can be run over
+    /// them with the job's settings.
+    pub fn inputs(&self) -> Result<Vec<Box<dyn GenericInputData<String>>>, MapReduceError> {
+        self.collect_inputs(&mut Vec::new())
+    }
+
+    fn execute<W, F>(&self, make_worker: F) -> Result<JobOutcome, MapReduceError>
+    where
+        W: MapReducer<Output = usize> + 'static,
+        F: Fn(Arc<dyn GenericInputData<String>>) -> W,
+    {
+        let pool = self.thread_pool()?;
+        let mut skipped = Vec::new();
+        let input_list = self.collect_inputs(&mut skipped)?;
+
+        // A deduplicated worker only knows the path of the first of its inputs,
+        // so the callback is given each input's own path.
+        let paths: Vec<Option<PathBuf>> = input_list
+            .iter()
+            .map(|input| input.path().map(PathBuf::from))
+            .collect();
+        let workers = if self.dedup {
+            create_workers_deduped(input_list, make_worker)
+        } else {
+            create_workers(input_list, make_worker)
+        };
+        let mut options = RunOptions::new().ordered(self.ordered);
+        if let Some(cancel) = &self.cancel {
+            options = options.cancel_flag(Arc::clone(cancel));
+        }
+        let summary = run_map_reduce_inspecting(&pool, workers, options, |index, worker| {
+            if let (Some(ResultHook(callback)), Some(path)) = (&self.on_result, &paths[index]) {
+                (callback.lock())(path, worker.get_result());
+            }
+        })?;
+        Ok(JobOutcome {
+            result: summary.result,
+            skipped,
+            inputs_processed: summary.inputs_processed,
+            inputs_failed: summary.inputs_failed,
+            cancelled: summary.cancelled,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::TempDir;
+
+    #[test]
+    fn every_named_worker_parses_from_its_name() {
+        for kind in NAMED_WORKERS {
+            assert_eq!(kind.name().parse::<WorkerKind>().unwrap(), *kind);
+        }
+    }
+
+    #[test]
+    fn words_builds_a_word_counter() {
+        let dir = TempDir::new();
+        dir.write("a.txt", "one two\nthree");
+        dir.write("b.txt", "four");
+
+        let kind: WorkerKind = "words".parse().unwrap();
+        assert_eq!(kind, WorkerKind::Words);
+        let words = MapReduceJob::new()
+            .data_dir(dir.path())
+            .worker(kind)
+            .run()
+            .unwrap();
+        assert_eq!(words, 4);
+    }
+
+    #[test]
+    fn unknown_worker_names_are_rejected() {
+        let error = "nope".parse::<WorkerKind>().unwrap_err();
+        assert!(matches!(error, MapReduceError::UnknownWorker(name) if name == "nope"));
+    }
+
+    fn fixture() -> TempDir {
+        let dir = TempDir::new();
+        dir.write("a.txt", "héllo world\n");
+        dir.write("b.txt", "one\ntwo three\n");
+        dir
+    }
+
+    #[test]
+    fn each_worker_kind_runs_over_the_same_fixture() {
+        let dir = fixture();
+        let job = MapReduceJob::new().data_dir(dir.path()).threads(2);
+
+        assert_eq!(job.clone().worker(WorkerKind::Lines).run().unwrap(), 3);
+        assert_eq!(job.clone().worker(WorkerKind::Words).run().unwrap(), 5);
+        assert_eq!(job.clone().worker(WorkerKind::Chars).run().unwrap(), 26);
+        assert_eq!(job.clone().worker(WorkerKind::Bytes).run().unwrap(), 27);
+        let matches = job.worker(WorkerKind::RegexMatches("o".to_string()));
+        assert_eq!(matches.run().unwrap(), 3);
+    }
+
+    #[test]
+    fn a_missing_data_dir_is_an_error() {
+        let dir = TempDir::new();
+        let missing = MapReduceJob::new()
+            .data_dir(dir.path().join("missing"))
+            .run();
+        assert!(matches!(missing, Err(MapReduceError::DirectoryNotFound(_))));
+        let unset = MapReduceJob::new().run();
+        assert!(matches!(unset, Err(MapReduceError::MissingDataDir)));
+        let no_threads = MapReduceJob::new().data_dir(dir.path()).threads(0).run();
+        assert!(matches!(
+            no_threads,
+            Err(MapReduceError::InvalidThreadCount)
+        ));
+    }
+
+    #[test]
+    fn empty_files_are_skipped_and_reported_when_asked() {
+        let dir = TempDir::new();
+        let empty = dir.write("empty.txt", "");
+        dir.write("full.txt", "one\ntwo");
+
+        let job = MapReduceJob::new().data_dir(dir.path());
+        let skipping = job
+            .clone()
+            .skip_empty(SkipEmpty(true))
+            .run_detailed()
+            .unwrap();
+        assert_eq!(skipping.result, 2);
+        assert_eq!(skipping.skipped, [empty]);
+
+        let keeping = job.run_detailed().unwrap();
+        assert_eq!(keeping.result, 2);
+        assert!(keeping.skipped.is_empty());
+    }
+
+    #[test]
+    fn a_set_cancel_flag_leaves_every_input_unmapped() {
+        let dir = TempDir::new();
+        dir.write("a.txt", "one\ntwo");
+        dir.write("b.txt", "three");
+
+        let cancel = Arc::new(AtomicBool::new(true));
+        let outcome = MapReduceJob::new()
+            .data_dir(dir.path())
+            .cancel_flag(cancel)
+            .run_detailed()
+            .unwrap();
+        assert_eq!(
+            (
+                outcome.result,
+                outcome.inputs_processed,
+                outcome.inputs_failed,
+                outcome.cancelled
+            ),
+            (0, 0, 0, 2)
+        );
+
+        let finished = MapReduceJob::new()
+            .data_dir(dir.path())
+            .run_detailed()
+            .unwrap();
+        assert_eq!((finished.result, finished.inputs_processed), (3, 2));
+        assert_eq!(finished.cancelled, 0);
+    }
+
+    #[test]
+    fn a_limit_caps_the_inputs_in_sorted_order() {
+        let dir = TempDir::new();
+        for (index, lines) in [1, 10, 100, 1000, 10000].into_iter().enumerate() {
+            dir.write(&format!("{index}.txt"), "line\n".repeat(lines));
+        }
+
+        let limited = MapReduceJob::new().data_dir(dir.path()).limit(2);
+        assert_eq!(limited.inputs().unwrap().len(), 2);
+        assert_eq!(limited.run().unwrap(), 11);
+    }
+
+    #[test]
+    fn the_result_hook_sees_each_input_once() {
+        let dir = TempDir::new();
+        let a = dir.write("a.txt", "one\ntwo");
+        let b = dir.write("b.txt", "three");
+        let copy = dir.write("copy-of-a.txt", "one\ntwo");
+        let expected = vec![(a, 2), (b, 1), (copy, 2)];
+
+        for (threads, ordered, dedup) in [(1, false, false), (4, false, false), (4, true, true)] {
+            let seen = Arc::new(Mutex::new(Vec::new()));
+            let hook_seen = Arc::clone(&seen);
+            let total = MapReduceJob::new()
+                .data_dir(dir.path())
+                .threads(threads)
+                .ordered(ordered)
+                .dedup(dedup)
+                .on_result(move |path, count| hook_seen.lock().push((path.to_path_buf(), count)))
+                .run()
+                .unwrap();
+
+            let mut seen = seen.lock().clone();
+            seen.sort();
+            assert_eq!(seen, expected);
+            assert_eq!(total, 5);
+        }
+    }
+}
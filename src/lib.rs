@@ -0,0 +1,636 @@
+//! Parallel map-reduce over files and other inputs.
+//!
+//! Each input is given to its own worker, workers are mapped in parallel on a
+//! thread pool, and the mapped workers are reduced into one result.
+//!
+//! ```
+//! use rs_map_reduce::{
+//!     create_workers, generate_inputs, run, run_map_reduce, RunOptions, WordCountWorker,
+//! };
+//!
+//! # let dir = std::env::temp_dir().join(format!("rs_map_reduce-doc-{}", std::process::id()));
+//! # std::fs::create_dir_all(&dir)?;
+//! std::fs::write(dir.join("a.txt"), "the quick brown fox")?;
+//! std::fs::write(dir.join("b.txt"), "jumps over\nthe lazy dog")?;
+//!
+//! let words = run(generate_inputs::<String>(&dir)?, WordCountWorker::new)?;
+//! assert_eq!(words, 9);
+//!
+//! // Workers can also be built first and run with options.
+//! let workers = create_workers(generate_inputs::<String>(&dir)?, WordCountWorker::new);
+//! let summary = run_map_reduce(workers, RunOptions::new())?;
+//! assert_eq!((summary.result, summary.inputs_processed), (9, 2));
+//! # std::fs::remove_dir_all(&dir)?;
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+
+mod aggregate;
+#[cfg(feature = "async")]
+mod asynchronous;
+mod cancel;
+mod checkpoint;
+mod error;
+mod fold;
+mod hash;
+mod incremental;
+mod input;
+mod job;
+mod options;
+mod output;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod pool;
+mod reduce;
+mod report;
+mod sample;
+mod streaming;
+#[cfg(test)]
+mod test_util;
+mod worker;
+
+use std::{collections::HashMap, path::PathBuf, sync::Arc, time::Instant};
+
+use parking_lot::Mutex;
+use reduce::{into_owned, reduce_mapped, reduce_ordered, DEFAULT_TREE_REDUCE_THRESHOLD};
+
+pub use aggregate::{aggregate_with, Aggregator, LineCountAggregator};
+#[cfg(feature = "async")]
+pub use asynchronous::{run_map_reduce_async, AsyncFileInputData, AsyncGenericInputData};
+pub use cancel::{CancellationToken, PartialResult};
+pub use checkpoint::{load_checkpoint, run_map_reduce_resumable, save_checkpoint, CheckpointEntry};
+pub use error::MapReduceError;
+pub use fold::run_with_fold;
+pub use hash::ContentHasher;
+pub use incremental::{load_progress, run_map_reduce_incremental, save_progress, FileProgress};
+pub use input::{
+    chunk_file, count_lines_parallel, generate_inputs, generate_inputs_binary,
+    generate_inputs_by_metadata, generate_inputs_filtered, generate_inputs_from_manifest,
+    generate_inputs_from_tar, generate_inputs_from_zip, generate_inputs_multi,
+    generate_inputs_recursive, ArchiveEntryInputData, BinaryFileInputData, CachedInputData,
+    FileChunkInputData, FileInputData, GenericInputData, HttpInputData, IterInputData, LineIter,
+    MmapFileInputData, ReaderInputData, RetryingInputData, StdinInputData, StringInputData,
+};
+pub use job::{JobOutcome, MapReduceJob, SkipEmpty, WorkerKind};
+pub use options::RunOptions;
+pub use output::WorkerOutput;
+#[cfg(feature = "rayon")]
+pub use parallel::run_map_reduce_rayon;
+pub use pool::{map_parallel, map_sequential, ProgressCallback, ThreadPool};
+pub use reduce::tree_reduce;
+pub use report::{
+    read_results_csv, read_results_gz, write_results_csv, write_results_gz, FileResult, JobResult,
+    PhaseTimings, RunSummary,
+};
+pub use sample::{run_map_reduce_sampled, sample_inputs, SampleEstimate};
+pub use streaming::{run_map_reduce_streaming, StreamedResults};
+pub use worker::{
+    create_workers, create_workers_deduped, create_workers_deduped_with, top_words,
+    AverageLineLengthWorker, ByteHistogramWorker, ByteSizeWorker, CharCountWorker,
+    CharFrequencyWorker, Combine, CompositeWorker, CountMode, DistinctLineWorker, DistinctLines,
+    DuplicateLineWorker, HeadTailOrder, HeadTailWorker, HyperLogLogWorker, JsonRecordCountWorker,
+    JsonRecords, LineCountWorker, LineLengthPercentileWorker, LinePredicate, LongestLineWorker,
+    MapReducer, Mapper, Metrics, MultiMetricWorker, MultiPatternCountWorker,
+    NonBlankLineCountWorker, NumberSum, Percentiles, PredicateLineWorker, Reducer,
+    RegexMatchCountWorker, RegexOptions, SumOfNumbersWorker, TextNormalizer, TopKWordWorker,
+    WeightedWorker, WordCountWorker, WordCounts, WordFrequencyWorker,
+};
+
+pub fn run<T, W, F>(
+    input_list: Vec<Box<dyn GenericInputData<T>>>,
+    make_worker: F,
+) -> Result<W::Output, MapReduceError>
+where
+    W: MapReducer + 'static,
+    F: Fn(Arc<dyn GenericInputData<T>>) -> W,
+{
+    aggregate(create_workers(input_list, make_worker))
+}
+
+pub fn aggregate<W>(workers: Vec<Arc<Mutex<W>>>) -> Result<W::Output, MapReduceError>
+where
+    W: MapReducer + 'static,
+{
+    run_map_reduce(workers, RunOptions::new()).map(|summary| summary.result)
+}
+
+pub fn run_map_reduce<W>(
+    workers: Vec<Arc<Mutex<W>>>,
+    options: RunOptions<'_>,
+) -> Result<RunSummary<W::Output>, MapReduceError>
+where
+    W: MapReducer + 'static,
+{
+    run_map_reduce_with_pool(&ThreadPool::default(), workers, options)
+}
+
+pub fn run_map_reduce_with_pool<W>(
+    pool: &ThreadPool,
+    workers: Vec<Arc<Mutex<W>>>,
+    options: RunOptions<'_>,
+) -> Result<RunSummary<W::Output>, MapReduceError>
+where
+    W: MapReducer + 'static,
+{
+    run_map_reduce_inspecting(pool, workers, options, |_, _| {})
+}
+
+/// Maps every worker and hands back the mapped workers, in input order,
+/// without reducing them, along with the inputs that failed. Only when no
+/// input could be mapped is the first failure returned, as in
+/// [`run_map_reduce`]. The built-in workers can be serialized at this point,
+/// without their input, and reduced somewhere else with [`reduce_all`].
+pub fn map_all<W>(workers: Vec<Arc<Mutex<W>>>) -> Result<MappedWorkers<W>, MapReduceError>
+where
+    W: MapReducer + 'static,
+{
+    map_all_with(&ThreadPool::default(), workers, RunOptions::new())
+}
+
+/// Like [`map_all`], on `pool` and with the map options of [`run_map_reduce`].
+pub fn map_all_with<W>(
+    pool: &ThreadPool,
+    workers: Vec<Arc<Mutex<W>>>,
+    options: RunOptions<'_>,
+) -> Result<MappedWorkers<W>, MapReduceError>
+where
+    W: MapReducer + 'static,
+{
+    let mut mapped = map_each(pool, workers, options);
+
+    if mapped.workers.is_empty() && !mapped.failures.is_empty() {
+        let (_, error) = mapped.failures.swap_remove(0);
+        return Err(error);
+    }
+    Ok(mapped)
+}
+
+/// Reduces workers from [`map_all`] with their own reduce, exactly as
+/// [`run_map_reduce`] does after mapping. No workers reduce to the identity.
+pub fn reduce_all<W>(mapped: Vec<W>) -> W::Output
+where
+    W: MapReducer,
+{
+    reduce_all_with(mapped, &RunOptions::new())
+}
+
+/// Like [`reduce_all`], with the reduce options of [`run_map_reduce`]:
+/// [`RunOptions::ordered`] and [`RunOptions::tree_reduce_threshold`].
+pub fn reduce_all_with<W>(mapped: Vec<W>, options: &RunOptions<'_>) -> W::Output
+where
+    W: MapReducer,
+{
+    let reduced = if options.ordered {
+        reduce_ordered(mapped)
+    } else {
+        let tree_threshold = options
+            .tree_reduce_threshold
+            .unwrap_or(DEFAULT_TREE_REDUCE_THRESHOLD);
+        reduce_mapped(mapped, tree_threshold)
+    };
+    reduced.get_result()
+}
+
+// The two steps of `map_all` and `reduce_all`, with the options applied and
+// failures recorded. `inspect` sees every successfully mapped worker once, in
+// input order, with its position in `workers`, just before it is reduced.
+fn run_map_reduce_inspecting<W>(
+    pool: &ThreadPool,
+    workers: Vec<Arc<Mutex<W>>>,
+    options: RunOptions<'_>,
+    mut inspect: impl FnMut(usize, &W),
+) -> Result<RunSummary<W::Output>, MapReduceError>
+where
+    W: MapReducer + 'static,
+{
+    // The map consumes the options, so the ones the reduce needs are kept.
+    let reduce_options = RunOptions {
+        ordered: options.ordered,
+        tree_reduce_threshold: options.tree_reduce_threshold,
+        ..RunOptions::new()
+    };
+    let fail_on_cancel = options.fail_on_cancel;
+    let started = Instant::now();
+    let mapped = map_all_with(pool, workers, options)?;
+    let map_duration = started.elapsed();
+
+    let inputs_processed = mapped.workers.len();
+    let reduce_started = Instant::now();
+    for (&index, worker) in mapped.indices.iter().zip(&mapped.workers) {
+        inspect(index, worker);
+    }
+    let result = reduce_all_with(mapped.workers, &reduce_options);
+    let timings = PhaseTimings {
+        map_duration,
+        reduce_duration: reduce_started.elapsed(),
+        total_duration: started.elapsed(),
+    };
+    log::info!(
+        "processed {} inputs ({} failed) in {:?} (map {:?}, reduce {:?})",
+        inputs_processed,
+        mapped.failures.len(),
+        timings.total_duration,
+        timings.map_duration,
+        timings.reduce_duration
+    );
+
+    if fail_on_cancel && mapped.cancelled > 0 {
+        let partial = PartialResult::new(result, inputs_processed);
+        return Err(MapReduceError::Cancelled(Some(partial)));
+    }
+
+    Ok(RunSummary {
+        result,
+        inputs_processed,
+        inputs_failed: mapped.failures.len(),
+        timed_out: mapped.timed_out,
+        cancelled: mapped.cancelled,
+        timings,
+        failures: mapped.failures,
+    })
+}
+
+/// Workers after [`map_all`]. They are owned outright, so reducing them takes
+/// no locks.
+pub struct MappedWorkers<W> {
+    /// The workers that mapped successfully, in input order.
+    pub workers: Vec<W>,
+    // Where each mapped worker was in the list given to `map_each`.
+    indices: Vec<usize>,
+    pub failures: Vec<(PathBuf, MapReduceError)>,
+    pub timed_out: usize,
+    pub cancelled: usize,
+}
+
+fn map_each<W>(
+    pool: &ThreadPool,
+    workers: Vec<Arc<Mutex<W>>>,
+    options: RunOptions<'_>,
+) -> MappedWorkers<W>
+where
+    W: MapReducer + 'static,
+{
+    // Duplicate inputs share one worker, which only needs to be mapped once.
+    let mut unique: Vec<Arc<Mutex<W>>> = Vec::new();
+    let mut positions = HashMap::new();
+    let slots: Vec<usize> = workers
+        .iter()
+        .map(|worker| {
+            *positions.entry(Arc::as_ptr(worker)).or_insert_with(|| {
+                unique.push(Arc::clone(worker));
+                unique.len() - 1
+            })
+        })
+        .collect();
+
+    // Paths are captured up front because a worker that times out keeps its
+    // lock for as long as its map is still running.
+    let paths: Vec<PathBuf> = unique
+        .iter()
+        .map(|worker| {
+            worker
+                .lock()
+                .input_path()
+                .map(PathBuf::from)
+                .unwrap_or_default()
+        })
+        .collect();
+    let outcomes = map_parallel(pool, &unique, options);
+
+    let mut failures = Vec::new();
+    let mut timed_out = 0;
+    let mut cancelled = 0;
+    let mut succeeded = Vec::with_capacity(unique.len());
+    for (path, outcome) in paths.into_iter().zip(outcomes) {
+        match outcome {
+            Ok(()) => succeeded.push(true),
+            Err(MapReduceError::Cancelled(_)) => {
+                cancelled += 1;
+                succeeded.push(false);
+            }
+            Err(error) => {
+                // An abandoned worker may still be mapping, so it is never reduced.
+                if matches!(error, MapReduceError::TimedOut(_)) {
+                    timed_out += 1;
+                }
+                failures.push((path, error));
+                succeeded.push(false);
+            }
+        }
+    }
+
+    let (indices, mapped): (Vec<usize>, Vec<Arc<Mutex<W>>>) = workers
+        .into_iter()
+        .enumerate()
+        .zip(slots)
+        .filter(|(_, slot)| succeeded[*slot])
+        .map(|(indexed, _)| indexed)
+        .unzip();
+    drop(unique);
+
+    MappedWorkers {
+        workers: into_owned(mapped),
+        indices,
+        failures,
+        timed_out,
+        cancelled,
+    }
+}
+
+pub fn run_with_breakdown<W>(
+    workers: Vec<Arc<Mutex<W>>>,
+) -> Result<JobResult<W::Output>, MapReduceError>
+where
+    W: MapReducer + 'static,
+{
+    run_with_breakdown_with_pool(&ThreadPool::default(), workers, RunOptions::new())
+        .map(|summary| summary.result)
+}
+
+/// Like [`run_with_breakdown`], with the options of [`run_map_reduce`] and the
+/// rest of its summary, e.g. how many inputs were cancelled.
+pub fn run_with_breakdown_with_pool<W>(
+    pool: &ThreadPool,
+    workers: Vec<Arc<Mutex<W>>>,
+    options: RunOptions<'_>,
+) -> Result<RunSummary<JobResult<W::Output>>, MapReduceError>
+where
+    W: MapReducer + 'static,
+{
+    let mut per_file = Vec::new();
+    let summary = run_map_reduce_inspecting(pool, workers, options, |_, worker| {
+        per_file.push(FileResult {
+            path: worker.input_path().map(PathBuf::from).unwrap_or_default(),
+            count: worker.get_result(),
+        });
+    })?;
+
+    Ok(RunSummary {
+        result: JobResult {
+            total: summary.result,
+            per_file,
+        },
+        inputs_processed: summary.inputs_processed,
+        inputs_failed: summary.inputs_failed,
+        timed_out: summary.timed_out,
+        cancelled: summary.cancelled,
+        timings: summary.timings,
+        failures: summary.failures,
+    })
+}
+
+pub fn collect_per_input<W>(workers: Vec<Arc<Mutex<W>>>) -> Vec<(PathBuf, W::Output)>
+where
+    W: MapReducer + 'static,
+{
+    let pool = ThreadPool::default();
+    let outcomes = map_parallel(&pool, &workers, RunOptions::new());
+
+    workers
+        .into_iter()
+        .zip(outcomes)
+        .filter(|(_, outcome)| outcome.is_ok())
+        .map(|(worker, _)| {
+            let worker = worker.lock();
+            let path = worker.input_path().map(PathBuf::from).unwrap_or_default();
+            (path, worker.get_result())
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use super::*;
+    use crate::test_util::{string_inputs, TempDir};
+
+    #[test]
+    fn run_map_reduce_returns_the_total() {
+        let workers = create_workers(string_inputs(&["a\nb", "c"]), LineCountWorker::new);
+        assert_eq!(
+            run_map_reduce(workers, RunOptions::new()).unwrap().result,
+            3
+        );
+    }
+
+    #[test]
+    fn no_workers_reduce_to_zero() {
+        let summary = run_map_reduce(Vec::<Arc<Mutex<LineCountWorker>>>::new(), RunOptions::new());
+        assert_eq!(summary.unwrap().result, 0);
+    }
+
+    #[test]
+    fn the_first_worker_is_mapped_too() {
+        let dir = TempDir::new();
+        dir.write("a.txt", "one\ntwo\nthree");
+        dir.write("b.txt", "four\nfive");
+
+        let input_list = generate_inputs::<String>(dir.path()).unwrap();
+        let workers = create_workers(input_list, LineCountWorker::new);
+        assert_eq!(aggregate(workers).unwrap(), 5);
+    }
+
+    #[test]
+    fn aggregate_handles_zero_one_and_many_workers() {
+        let aggregate_lines = |contents: &[&str]| {
+            aggregate(create_workers(
+                string_inputs(contents),
+                LineCountWorker::new,
+            ))
+        };
+
+        assert_eq!(aggregate_lines(&[]).unwrap(), 0);
+        assert_eq!(aggregate_lines(&["one\ntwo"]).unwrap(), 2);
+        assert_eq!(
+            aggregate_lines(&["one\ntwo", "three", "four\nfive\nsix"]).unwrap(),
+            6
+        );
+    }
+
+    #[test]
+    fn an_unreadable_input_is_skipped() {
+        let dir = TempDir::new();
+        dir.write("a.txt", "one\ntwo");
+        let unreadable = dir.write("b.txt", "three");
+        dir.write("c.txt", "four\nfive\nsix");
+
+        let input_list = generate_inputs::<String>(dir.path()).unwrap();
+        // Permissions do not stop root, so the file is removed once listed.
+        std::fs::remove_file(&unreadable).unwrap();
+        let workers = create_workers(input_list, LineCountWorker::new);
+        let summary = run_map_reduce(workers, RunOptions::new()).unwrap();
+
+        assert_eq!(summary.result, 5);
+        assert_eq!(summary.inputs_processed, 2);
+        assert_eq!(summary.inputs_failed, 1);
+        assert_eq!(summary.failures[0].0, unreadable);
+    }
+
+    #[test]
+    fn a_directory_read_as_a_file_fails_alone() {
+        let dir = TempDir::new();
+        let a = dir.write("a.txt", "one\ntwo");
+        let c = dir.write("c.txt", "three");
+        let not_a_file = dir.path().join("b");
+        std::fs::create_dir(&not_a_file).unwrap();
+
+        let input_list: Vec<Box<dyn GenericInputData<String>>> = [a, not_a_file.clone(), c]
+            .into_iter()
+            .map(|path| Box::new(FileInputData::new(path)) as Box<dyn GenericInputData<String>>)
+            .collect();
+        let workers = create_workers(input_list, LineCountWorker::new);
+        let summary = run_map_reduce(workers, RunOptions::new()).unwrap();
+
+        assert_eq!((summary.result, summary.inputs_processed), (3, 2));
+        assert_eq!(summary.failures.len(), 1);
+        assert_eq!(summary.failures[0].0, not_a_file);
+        assert!(matches!(summary.failures[0].1, MapReduceError::Io(_)));
+    }
+
+    #[test]
+    fn per_input_results_keep_their_paths() {
+        let dir = TempDir::new();
+        let a = dir.write("a.txt", "one\ntwo\nthree");
+        let b = dir.write("b.txt", "four");
+
+        let input_list = generate_inputs::<String>(dir.path()).unwrap();
+        let per_input = collect_per_input(create_workers(input_list, LineCountWorker::new));
+        assert_eq!(per_input, [(a, 3), (b, 1)]);
+    }
+
+    struct SleepingInput(Duration);
+
+    impl GenericInputData<String> for SleepingInput {
+        fn read(&self) -> Result<String, MapReduceError> {
+            thread::sleep(self.0);
+            Ok("late\nlines".to_string())
+        }
+    }
+
+    #[test]
+    fn slow_inputs_are_abandoned_after_the_timeout() {
+        let mut input_list = string_inputs(&["a\nb", "c"]);
+        input_list.push(Box::new(SleepingInput(Duration::from_secs(2))));
+
+        let workers = create_workers(input_list, LineCountWorker::new);
+        let options = RunOptions::new().per_worker_timeout(Duration::from_millis(50));
+        let summary = run_map_reduce(workers, options).unwrap();
+        assert_eq!(summary.result, 3);
+        assert_eq!((summary.timed_out, summary.inputs_failed), (1, 1));
+        assert!(matches!(summary.failures[0].1, MapReduceError::TimedOut(_)));
+    }
+
+    #[test]
+    fn each_phase_is_timed() {
+        let mut input_list = string_inputs(&["a\nb"; 64]);
+        input_list.push(Box::new(SleepingInput(Duration::from_millis(20))));
+
+        let workers = create_workers(input_list, LineCountWorker::new);
+        let timings = run_map_reduce(workers, RunOptions::new()).unwrap().timings;
+        assert!(timings.map_duration >= Duration::from_millis(20));
+        assert!(timings.reduce_duration > Duration::ZERO);
+        assert!(timings.total_duration >= timings.map_duration + timings.reduce_duration);
+    }
+
+    #[test]
+    fn the_summary_tells_empty_inputs_from_no_inputs() {
+        let dir = TempDir::new();
+        dir.write("empty.txt", "");
+        dir.write("lines.txt", "a\nb");
+        let missing = dir.write("removed.txt", "c");
+        let input_list = generate_inputs::<String>(dir.path()).unwrap();
+        std::fs::remove_file(missing).unwrap();
+
+        let summary = run_map_reduce(
+            create_workers(input_list, LineCountWorker::new),
+            RunOptions::new(),
+        )
+        .unwrap();
+        assert_eq!(
+            (
+                summary.result,
+                summary.inputs_processed,
+                summary.inputs_failed
+            ),
+            (2, 2, 1)
+        );
+
+        let no_inputs =
+            run_map_reduce(Vec::<Arc<Mutex<LineCountWorker>>>::new(), RunOptions::new()).unwrap();
+        assert_eq!(
+            (
+                no_inputs.result,
+                no_inputs.inputs_processed,
+                no_inputs.inputs_failed
+            ),
+            (0, 0, 0)
+        );
+    }
+
+    // Maps, sends the mapped workers through JSON the way they would be
+    // shipped elsewhere, and reduces what comes back.
+    fn reduce_after_round_trip<W>(workers: Vec<Arc<Mutex<W>>>) -> W::Output
+    where
+        W: MapReducer + serde::Serialize + serde::de::DeserializeOwned + 'static,
+    {
+        let json = serde_json::to_string(&map_all(workers).unwrap().workers).unwrap();
+        reduce_all(serde_json::from_str::<Vec<W>>(&json).unwrap())
+    }
+
+    #[test]
+    fn separate_map_and_reduce_steps_match_a_single_run() {
+        let contents = ["one\ntwo\nthree", "two\nfour", "a much longer line\none"];
+
+        let one_shot = run(string_inputs(&contents), LineCountWorker::new).unwrap();
+        let stepwise = reduce_after_round_trip(create_workers(
+            string_inputs(&contents),
+            LineCountWorker::new,
+        ));
+        assert_eq!(stepwise, one_shot);
+        assert_eq!(stepwise, 7);
+
+        assert_eq!(
+            reduce_after_round_trip(create_workers(
+                string_inputs(&contents),
+                LongestLineWorker::new
+            )),
+            run(string_inputs(&contents), LongestLineWorker::new).unwrap()
+        );
+        assert_eq!(
+            reduce_after_round_trip(create_workers(
+                string_inputs(&contents),
+                DistinctLineWorker::new
+            )),
+            run(string_inputs(&contents), DistinctLineWorker::new).unwrap()
+        );
+
+        assert_eq!(reduce_all(Vec::<LineCountWorker>::new()), 0);
+        let mapped = map_all(create_workers(
+            string_inputs(&contents),
+            LineCountWorker::new,
+        ));
+        let tree = RunOptions::new().tree_reduce_threshold(1);
+        assert_eq!(reduce_all_with(mapped.unwrap().workers, &tree), 7);
+    }
+
+    #[test]
+    fn map_all_hands_back_the_failed_inputs() {
+        let dir = TempDir::new();
+        dir.write("kept.txt", "a\nb");
+        let missing = dir.write("removed.txt", "c");
+        let input_list = generate_inputs::<String>(dir.path()).unwrap();
+        std::fs::remove_file(&missing).unwrap();
+
+        let mapped = map_all(create_workers(input_list, LineCountWorker::new)).unwrap();
+        assert_eq!(mapped.workers.len(), 1);
+        assert_eq!(mapped.failures.len(), 1);
+        assert_eq!(mapped.failures[0].0, missing);
+        assert!(matches!(mapped.failures[0].1, MapReduceError::Io(_)));
+        assert_eq!(reduce_all(mapped.workers), 2);
+
+        let input_list = generate_inputs::<String>(dir.path()).unwrap();
+        std::fs::remove_file(dir.path().join("kept.txt")).unwrap();
+        let nothing_mapped = map_all(create_workers(input_list, LineCountWorker::new));
+        assert!(matches!(nothing_mapped, Err(MapReduceError::Io(_))));
+    }
+}
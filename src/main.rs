@@ -1,125 +1,580 @@
 use std::{
-    fs::{self, File},
-    io::Read,
+    fmt::{Debug, Display},
+    fs, mem,
+    num::NonZeroUsize,
     path::{Path, PathBuf},
-    sync::Arc,
+    process,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
 };
 
+use clap::{ArgGroup, Parser, ValueEnum};
 use parking_lot::Mutex;
+use regex::Regex;
+use rs_map_reduce::{
+    create_workers, run_map_reduce_with_pool, run_with_breakdown_with_pool, top_words,
+    write_results_csv, AverageLineLengthWorker, ByteSizeWorker, CharCountWorker,
+    CharFrequencyWorker, CountMode, DistinctLineWorker, DuplicateLineWorker, FileResult,
+    GenericInputData, HyperLogLogWorker, JobResult, JsonRecordCountWorker, LineCountWorker,
+    LineLengthPercentileWorker, LongestLineWorker, MapReduceJob, MapReducer, MultiMetricWorker,
+    NonBlankLineCountWorker, RegexMatchCountWorker, RunOptions, RunSummary, SampleEstimate,
+    StdinInputData, SumOfNumbersWorker, ThreadPool, TopKWordWorker, WordCountWorker,
+    WordFrequencyWorker, WorkerKind,
+};
+use serde::Serialize;
 
-trait GenericInputData<T> {
-    fn read(&self) -> T;
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Format {
+    Text,
+    Json,
 }
 
-trait Mapper {
-    fn map(&mut self);
-}
+const TOP_WORDS: usize = 10;
+
+#[derive(Parser)]
+#[command(about = "Count lines, words and more across files in parallel")]
+#[command(group(ArgGroup::new("inputs").args(["paths", "dirs"]).multiple(true)))]
+struct Args {
+    /// What to compute
+    #[arg(long, value_enum, default_value_t = WorkerKind::Lines)]
+    worker: WorkerKind,
+
+    /// Combine JSON results, given as paths, from earlier `--format json` runs
+    #[arg(long, conflicts_with_all = ["worker", "dirs", "list", "output", "sample"])]
+    merge: bool,
+
+    /// Directories to read inputs from, or with `--merge` the results to
+    /// combine; standard input is read if none are given
+    paths: Vec<String>,
+
+    /// Another directory to read inputs from
+    #[arg(long = "dir", value_name = "DIR")]
+    dirs: Vec<String>,
+
+    /// Descend into subdirectories
+    #[arg(long)]
+    recursive: bool,
+
+    /// Only read files whose names match this glob
+    #[arg(long, conflicts_with = "recursive")]
+    pattern: Option<String>,
+
+    /// Number of threads to map inputs on [default: available parallelism]
+    #[arg(long)]
+    threads: Option<NonZeroUsize>,
+
+    #[arg(long, value_enum, default_value_t = Format::Text)]
+    format: Format,
+
+    /// Print the inputs that would be read without reading them
+    #[arg(long)]
+    list: bool,
 
-trait Reducer {
-    fn reduce(&mut self, other: &dyn MapReducer);
-    fn get_result(&self) -> usize;
+    /// Also write each input's count to this file as CSV; only counting
+    /// workers such as `lines` or `words` are supported, over directories
+    #[arg(long, value_name = "FILE", requires = "inputs")]
+    output: Option<PathBuf>,
+
+    /// Only read this fraction of the inputs, picked at random, and estimate
+    /// the total for all of them; only counting workers are supported, over
+    /// directories
+    #[arg(long, value_name = "FRACTION", requires = "inputs")]
+    sample: Option<f64>,
+
+    /// Seed for picking the `--sample`, so that runs can be repeated
+    #[arg(long, default_value_t = 0, requires = "sample")]
+    seed: u64,
 }
 
-trait MapReducer: Mapper + Reducer {}
+struct CountOptions<'a> {
+    format: Format,
+    output: Option<&'a Path>,
+    sample: Option<f64>,
+    seed: u64,
+}
 
-#[derive(Clone)]
-struct LineCountWorker {
-    input_data: Arc<dyn GenericInputData<String>>,
-    result: usize,
+fn fail(message: impl Display) -> ! {
+    eprintln!("Error: {message}");
+    process::exit(1);
 }
 
-impl Mapper for LineCountWorker {
-    fn map(&mut self) {
-        let data = self.input_data.read();
-        self.result = data.lines().count();
-    }
+// What every run shares: the pool to map on and the flag Ctrl-C sets.
+struct RunContext {
+    pool: ThreadPool,
+    interrupted: Arc<AtomicBool>,
 }
 
-impl Reducer for LineCountWorker {
-    fn reduce(&mut self, other: &dyn MapReducer) {
-        self.result += other.get_result();
+impl RunContext {
+    fn options(&self) -> RunOptions<'static> {
+        RunOptions::new().cancel_flag(Arc::clone(&self.interrupted))
     }
+}
 
-    fn get_result(&self) -> usize {
-        self.result
-    }
+// Ctrl-C stops new inputs from being mapped; the ones already finished are
+// still reduced and reported.
+fn interrupt_flag() -> Arc<AtomicBool> {
+    let interrupted = Arc::new(AtomicBool::new(false));
+    let handler_flag = Arc::clone(&interrupted);
+    ctrlc::set_handler(move || handler_flag.store(true, Ordering::SeqCst))
+        .unwrap_or_else(|error| fail(error));
+    interrupted
 }
 
-impl MapReducer for LineCountWorker {}
+fn interruption(cancelled: usize, inputs_finished: usize) -> Option<String> {
+    (cancelled > 0).then(|| format!("Interrupted after {inputs_finished} files"))
+}
 
-struct FileInputData {
-    file_path: PathBuf,
+fn summarize<W, F>(
+    context: &RunContext,
+    input_list: Vec<Box<dyn GenericInputData<String>>>,
+    make_worker: F,
+) -> RunSummary<W::Output>
+where
+    W: MapReducer + 'static,
+    F: Fn(Arc<dyn GenericInputData<String>>) -> W,
+{
+    let workers = create_workers(input_list, make_worker);
+    run_map_reduce_with_pool(&context.pool, workers, context.options())
+        .unwrap_or_else(|error| fail(error))
 }
 
-impl FileInputData {
-    fn new(file_path: PathBuf) -> Self {
-        Self { file_path }
+fn print_inputs<T>(summary: &RunSummary<T>) {
+    let inputs_finished = summary.inputs_processed + summary.inputs_failed;
+    if let Some(interruption) = interruption(summary.cancelled, inputs_finished) {
+        println!("{interruption}");
+    }
+    println!("Inputs processed: {}", summary.inputs_processed);
+    println!("Inputs failed: {}", summary.inputs_failed);
+    for (path, error) in &summary.failures {
+        eprintln!("Failed to process {}: {error}", path.display());
     }
 }
 
-impl<T> GenericInputData<T> for FileInputData
-where
-    T: From<String>,
+fn report<W, F>(
+    label: &str,
+    format: Format,
+    context: &RunContext,
+    input_list: Vec<Box<dyn GenericInputData<String>>>,
+    make_worker: F,
+) where
+    W: MapReducer + 'static,
+    W::Output: Debug + Serialize,
+    F: Fn(Arc<dyn GenericInputData<String>>) -> W,
 {
-    fn read(&self) -> T {
-        let mut file = File::open(&self.file_path).expect("Failed to open file");
-        let mut content = String::new();
-        file.read_to_string(&mut content)
-            .expect("Failed to read file");
-        T::from(content)
+    match format {
+        Format::Text => {
+            let summary = summarize(context, input_list, make_worker);
+            println!("{label}: {:?}", summary.result);
+            print_inputs(&summary);
+        }
+        Format::Json => {
+            let workers = create_workers(input_list, make_worker);
+            let summary = run_with_breakdown_with_pool(&context.pool, workers, context.options())
+                .unwrap_or_else(|error| fail(error));
+            // Kept off stdout so that it still holds nothing but the JSON.
+            let inputs_finished = summary.inputs_processed + summary.inputs_failed;
+            if let Some(interruption) = interruption(summary.cancelled, inputs_finished) {
+                eprintln!("{interruption}");
+            }
+            let json =
+                serde_json::to_string_pretty(&summary.result).unwrap_or_else(|error| fail(error));
+            println!("{json}");
+        }
     }
 }
 
-fn generate_inputs<T>(data_dir: &str) -> Vec<Box<dyn GenericInputData<T>>>
-where
-    T: From<String>,
-{
-    let path = Path::new(data_dir);
-    let mut inputs = Vec::new();
-    if path.is_dir() {
-        for entry in fs::read_dir(path)
-            .expect("Failed to read directory")
-            .flatten()
-        {
-            let file_path = entry.path();
-            let input_data =
-                Box::new(FileInputData::new(file_path)) as Box<dyn GenericInputData<T>>;
-            inputs.push(input_data);
+#[derive(Serialize)]
+struct SampledResult {
+    #[serde(flatten)]
+    result: JobResult,
+    estimate: SampleEstimate,
+}
+
+// Counting workers are run through the job itself, which hands each input's
+// count to `on_result` for the CSV.
+fn report_counts(
+    label: &str,
+    job: MapReduceJob,
+    interrupted: Arc<AtomicBool>,
+    options: &CountOptions<'_>,
+) {
+    let (job, inputs_total) = match options.sample {
+        Some(fraction) => {
+            let inputs_total = job.inputs().unwrap_or_else(|error| fail(error)).len();
+            (job.sample(fraction, options.seed), inputs_total)
+        }
+        None => (job, 0),
+    };
+
+    let rows = Arc::new(Mutex::new(Vec::new()));
+    let collected = Arc::clone(&rows);
+    let outcome = job
+        .cancel_flag(interrupted)
+        .on_result(move |path, count| collected.lock().push((path.to_path_buf(), count)))
+        .run_detailed()
+        .unwrap_or_else(|error| fail(error));
+    let rows: Vec<(PathBuf, usize)> = mem::take(&mut *rows.lock());
+    let total = outcome.result;
+    let estimate = options
+        .sample
+        .map(|_| SampleEstimate::new(total, rows.len(), inputs_total));
+
+    // In JSON, everything but the result goes to stderr so that stdout holds
+    // nothing but the JSON.
+    let json = options.format == Format::Json;
+    let note = |line: String| {
+        if json {
+            eprintln!("{line}");
+        } else {
+            println!("{line}");
         }
+    };
+    let inputs_finished = outcome.inputs_processed + outcome.inputs_failed;
+    if let Some(interruption) = interruption(outcome.cancelled, inputs_finished) {
+        note(interruption);
+    }
+    if let Some(out_path) = options.output {
+        write_results_csv(&rows, out_path).unwrap_or_else(|error| fail(error));
+        note(format!(
+            "Wrote {} results to {}",
+            rows.len(),
+            out_path.display()
+        ));
+    }
+
+    if json {
+        let result = JobResult {
+            total,
+            per_file: rows
+                .into_iter()
+                .map(|(path, count)| FileResult { path, count })
+                .collect(),
+        };
+        let json = match estimate {
+            Some(estimate) => serde_json::to_string_pretty(&SampledResult { result, estimate }),
+            None => serde_json::to_string_pretty(&result),
+        };
+        println!("{}", json.unwrap_or_else(|error| fail(error)));
+        return;
+    }
+
+    println!("{label}: {total}");
+    if let Some(estimate) = estimate {
+        println!(
+            "Sampled {} of {} inputs",
+            estimate.inputs_sampled, estimate.inputs_total
+        );
+        println!("Estimated total: {}", estimate.estimate);
     }
-    inputs
 }
 
-fn create_workers(
+fn report_top_words(
+    format: Format,
+    context: &RunContext,
     input_list: Vec<Box<dyn GenericInputData<String>>>,
-) -> Vec<Arc<Mutex<dyn MapReducer>>> {
-    let mut workers = Vec::new();
+) {
+    if format == Format::Json {
+        return report(
+            WorkerKind::WordFrequency.label(),
+            format,
+            context,
+            input_list,
+            WordFrequencyWorker::new,
+        );
+    }
 
-    for input_data in input_list {
-        let worker = Arc::new(Mutex::new(LineCountWorker {
-            input_data: input_data.into(),
-            result: 0,
-        }));
+    let summary = summarize(context, input_list, WordFrequencyWorker::new);
+    if summary.result.approximate {
+        println!("Top words (approximate):");
+    } else {
+        println!("Top words:");
+    }
+    for (word, count) in top_words(&summary.result.counts, TOP_WORDS) {
+        println!("  {word}: {count}");
+    }
+    print_inputs(&summary);
+}
 
-        workers.push(worker as Arc<Mutex<dyn MapReducer>>);
+fn list_inputs(input_list: &[Box<dyn GenericInputData<String>>]) {
+    for input in input_list {
+        match input.path() {
+            Some(path) => println!("{}", path.display()),
+            None => println!("<stdin>"),
+        }
     }
+    println!("Inputs: {}", input_list.len());
+}
 
-    workers
+fn merge_reports(format: Format, paths: Vec<String>) {
+    let merged = paths
+        .iter()
+        .map(|path| {
+            let json = fs::read_to_string(path).unwrap_or_else(|error| fail(error));
+            serde_json::from_str::<JobResult>(&json)
+                .unwrap_or_else(|error| fail(format!("{path} is not a job result: {error}")))
+        })
+        .reduce(JobResult::merge)
+        .unwrap_or_else(|| fail("merge requires at least one result file"));
+
+    match format {
+        Format::Text => {
+            println!("Total: {}", merged.total);
+            println!("Files: {}", merged.per_file.len());
+        }
+        Format::Json => {
+            let json = serde_json::to_string_pretty(&merged).unwrap_or_else(|error| fail(error));
+            println!("{json}");
+        }
+    }
 }
 
 fn main() {
-    let input_list = generate_inputs::<String>("test_inputs");
-    let mut workers = create_workers(input_list);
+    env_logger::init();
+    let args = Args::parse();
+
+    if args.merge {
+        return merge_reports(args.format, args.paths);
+    }
+
+    let mut job = MapReduceJob::new()
+        .recursive(args.recursive)
+        .worker(args.worker.clone());
+    if let Some(threads) = args.threads {
+        job = job.threads(threads.get());
+    }
+    if let Some(pattern) = &args.pattern {
+        job = job.pattern(pattern);
+    }
+    let read_stdin = args.paths.is_empty() && args.dirs.is_empty();
+    for data_dir in args.paths.iter().chain(&args.dirs) {
+        job = job.data_dir(data_dir);
+    }
+
+    let worker_kind = args.worker;
+    let label = worker_kind.label();
+    let interrupted = interrupt_flag();
+    if args.output.is_some() || args.sample.is_some() {
+        let options = CountOptions {
+            format: args.format,
+            output: args.output.as_deref(),
+            sample: args.sample,
+            seed: args.seed,
+        };
+        return report_counts(label, job, interrupted, &options);
+    }
 
-    if let Some(first_worker) = workers.first().cloned() {
-        let mut first_worker = first_worker.lock();
+    let input_list = if read_stdin {
+        vec![Box::new(StdinInputData::new()) as Box<dyn GenericInputData<String>>]
+    } else {
+        job.inputs().unwrap_or_else(|error| fail(error))
+    };
+    // Listing only resolves paths; no input is opened or read.
+    if args.list {
+        return list_inputs(&input_list);
+    }
 
-        for worker in workers.iter_mut().skip(1) {
-            let mut worker = worker.lock();
-            worker.map();
-            first_worker.reduce(&*worker);
+    let format = args.format;
+    let context = RunContext {
+        pool: job.thread_pool().unwrap_or_else(|error| fail(error)),
+        interrupted,
+    };
+    match worker_kind {
+        WorkerKind::Lines => report(label, format, &context, input_list, LineCountWorker::new),
+        WorkerKind::Words => report(label, format, &context, input_list, WordCountWorker::new),
+        WorkerKind::Chars => report(label, format, &context, input_list, |input| {
+            CharCountWorker::new(input, CountMode::Chars)
+        }),
+        WorkerKind::Bytes => report(label, format, &context, input_list, ByteSizeWorker::new),
+        WorkerKind::NonBlankLines => report(
+            label,
+            format,
+            &context,
+            input_list,
+            NonBlankLineCountWorker::new,
+        ),
+        WorkerKind::AverageLineLength => report(
+            label,
+            format,
+            &context,
+            input_list,
+            AverageLineLengthWorker::new,
+        ),
+        WorkerKind::Metrics => report(label, format, &context, input_list, MultiMetricWorker::new),
+        WorkerKind::LineLengthPercentiles => report(
+            label,
+            format,
+            &context,
+            input_list,
+            LineLengthPercentileWorker::new,
+        ),
+        WorkerKind::LongestLine => {
+            report(label, format, &context, input_list, LongestLineWorker::new)
+        }
+        WorkerKind::DuplicateLines => report(
+            label,
+            format,
+            &context,
+            input_list,
+            DuplicateLineWorker::new,
+        ),
+        WorkerKind::DistinctLines => {
+            report(label, format, &context, input_list, DistinctLineWorker::new)
         }
+        WorkerKind::ApproxDistinctLines => {
+            report(label, format, &context, input_list, HyperLogLogWorker::new)
+        }
+        WorkerKind::Sum => report(label, format, &context, input_list, SumOfNumbersWorker::new),
+        WorkerKind::ApproxTopWords => report(label, format, &context, input_list, |input| {
+            TopKWordWorker::new(input, TOP_WORDS)
+        }),
+        WorkerKind::WordFrequency => report_top_words(format, &context, input_list),
+        WorkerKind::CharFrequency => report(
+            label,
+            format,
+            &context,
+            input_list,
+            CharFrequencyWorker::new,
+        ),
+        WorkerKind::JsonRecords => report(
+            label,
+            format,
+            &context,
+            input_list,
+            JsonRecordCountWorker::new,
+        ),
+        WorkerKind::RegexMatches(pattern) => {
+            let pattern = Regex::new(&pattern).unwrap_or_else(|error| fail(error));
+            report(label, format, &context, input_list, |input| {
+                RegexMatchCountWorker::new(input, pattern.clone())
+            })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::error::ErrorKind;
+
+    use super::*;
+
+    fn parse_error(args: &[&str]) -> ErrorKind {
+        Args::try_parse_from(args)
+            .err()
+            .expect("arguments should be rejected")
+            .kind()
+    }
+
+    #[test]
+    fn defaults_to_counting_lines_as_text() {
+        let args = Args::try_parse_from(["rs_map_reduce"]).unwrap();
+        assert_eq!(args.worker, WorkerKind::Lines);
+        assert!(args.format == Format::Text);
+        assert!(args.paths.is_empty() && args.dirs.is_empty());
+        assert!(!args.recursive && args.threads.is_none());
+    }
+
+    #[test]
+    fn parses_worker_dirs_threads_and_format() {
+        let args = Args::try_parse_from([
+            "rs_map_reduce",
+            "--worker",
+            "words",
+            "--dir",
+            "logs",
+            "--dir",
+            "archive",
+            "--threads",
+            "4",
+            "--recursive",
+            "--format",
+            "json",
+        ])
+        .unwrap();
+        assert_eq!(args.worker, WorkerKind::Words);
+        assert_eq!(args.dirs, ["logs", "archive"]);
+        assert_eq!(args.threads, NonZeroUsize::new(4));
+        assert!(args.recursive);
+        assert!(args.format == Format::Json);
+    }
+
+    #[test]
+    fn positional_arguments_are_all_directories() {
+        let args = Args::try_parse_from(["rs_map_reduce", "d1", "d2"]).unwrap();
+        assert_eq!(args.worker, WorkerKind::Lines);
+        assert_eq!(args.paths, ["d1", "d2"]);
+    }
+
+    #[test]
+    fn rejects_unknown_workers_and_bad_values() {
+        assert_eq!(
+            parse_error(&["rs_map_reduce", "--worker", "nope"]),
+            ErrorKind::InvalidValue
+        );
+        assert_eq!(
+            parse_error(&["rs_map_reduce", "--threads", "0"]),
+            ErrorKind::ValueValidation
+        );
+        assert_eq!(
+            parse_error(&["rs_map_reduce", "--format", "xml"]),
+            ErrorKind::InvalidValue
+        );
+    }
+
+    #[test]
+    fn rejects_conflicting_arguments() {
+        assert_eq!(
+            parse_error(&["rs_map_reduce", "--recursive", "--pattern", "*.log"]),
+            ErrorKind::ArgumentConflict
+        );
+        assert_eq!(
+            parse_error(&["rs_map_reduce", "--merge", "--worker", "words", "a.json"]),
+            ErrorKind::ArgumentConflict
+        );
+        assert_eq!(
+            parse_error(&["rs_map_reduce", "--seed", "3"]),
+            ErrorKind::MissingRequiredArgument
+        );
+    }
+
+    #[test]
+    fn sampling_and_csv_output_need_a_directory() {
+        for args in [
+            &["rs_map_reduce", "--sample", "0.1"][..],
+            &["rs_map_reduce", "--output", "counts.csv"],
+        ] {
+            assert_eq!(parse_error(args), ErrorKind::MissingRequiredArgument);
+        }
+
+        let args =
+            Args::try_parse_from(["rs_map_reduce", "--dir", "logs", "--sample", "0.1"]).unwrap();
+        assert_eq!(args.sample, Some(0.1));
+        let args = Args::try_parse_from(["rs_map_reduce", "logs", "--output", "counts.csv"]);
+        assert_eq!(args.unwrap().output, Some(PathBuf::from("counts.csv")));
+    }
+
+    struct UnreadableInput(PathBuf);
+
+    impl GenericInputData<String> for UnreadableInput {
+        fn read(&self) -> Result<String, rs_map_reduce::MapReduceError> {
+            panic!("{} was read while listing", self.0.display());
+        }
+
+        fn path(&self) -> Option<&Path> {
+            Some(&self.0)
+        }
+    }
+
+    #[test]
+    fn listing_inputs_never_reads_them() {
+        let args = Args::try_parse_from(["rs_map_reduce", "--list", "logs"]).unwrap();
+        assert!(args.list);
 
-        println!("Lines: {}", first_worker.get_result());
+        let input_list: Vec<Box<dyn GenericInputData<String>>> = vec![
+            Box::new(UnreadableInput(PathBuf::from("logs/a.txt"))),
+            Box::new(UnreadableInput(PathBuf::from("logs/b.txt"))),
+        ];
+        list_inputs(&input_list);
     }
 }
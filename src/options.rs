@@ -0,0 +1,65 @@
+use std::{
+    sync::{atomic::AtomicBool, Arc},
+    time::Duration,
+};
+
+use crate::{CancellationToken, ProgressCallback};
+
+#[derive(Default)]
+pub struct RunOptions<'a> {
+    pub(crate) progress: Option<ProgressCallback<'a>>,
+    pub(crate) per_worker_timeout: Option<Duration>,
+    pub(crate) cancel: Option<CancellationToken>,
+    pub(crate) fail_on_cancel: bool,
+    pub(crate) tree_reduce_threshold: Option<usize>,
+    pub(crate) ordered: bool,
+}
+
+impl<'a> RunOptions<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn progress(mut self, progress: impl FnMut(usize, usize) + 'a) -> Self {
+        self.progress = Some(Box::new(progress));
+        self
+    }
+
+    pub fn per_worker_timeout(mut self, timeout: Duration) -> Self {
+        self.per_worker_timeout = Some(timeout);
+        self
+    }
+
+    /// Workers that have not started mapping by the time `cancel` is set are
+    /// skipped, and the run reduces whatever has finished.
+    pub fn cancel_flag(mut self, cancel: Arc<AtomicBool>) -> Self {
+        self.cancel = Some(cancel.into());
+        self
+    }
+
+    /// Like [`cancel_flag`](Self::cancel_flag), but a cancelled run fails with
+    /// [`MapReduceError::Cancelled`](crate::MapReduceError::Cancelled), which
+    /// carries whatever was reduced before cancellation.
+    pub fn cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancel = Some(token);
+        self.fail_on_cancel = true;
+        self
+    }
+
+    /// Runs with at least `threshold` mapped workers are reduced pairwise in
+    /// parallel rather than folded serially. Defaults to 16.
+    pub fn tree_reduce_threshold(mut self, threshold: usize) -> Self {
+        self.tree_reduce_threshold = Some(threshold);
+        self
+    }
+
+    /// Reduces mapped workers one at a time in input path order instead of
+    /// pairing them up across threads. Workers whose result depends on the
+    /// order of reduction need this to give the same answer on every run:
+    /// `TopKWordWorker`, which drops counters as it merges, and
+    /// `HeadTailWorker` with `HeadTailOrder::Input`.
+    pub fn ordered(mut self, ordered: bool) -> Self {
+        self.ordered = ordered;
+        self
+    }
+}
@@ -0,0 +1,222 @@
+use std::{collections::HashMap, fmt};
+
+use serde::{Deserialize, Serialize};
+
+use crate::MapReduceError;
+
+/// A worker result in a form that does not depend on the worker's type, so
+/// results from different kinds of worker can be printed side by side. Each
+/// variant merges the way the workers that produce it reduce.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum WorkerOutput {
+    /// A count, merged by adding.
+    Count(usize),
+    /// A sum, merged by adding.
+    Float(f64),
+    /// Counts per key, merged by adding. `approximate` is set when some of
+    /// the counts are estimates, and stays set through a merge.
+    Histogram {
+        counts: HashMap<String, usize>,
+        approximate: bool,
+    },
+    /// Merged by taking the larger.
+    Max(usize),
+    /// Kept as its parts so that two means can be merged.
+    Mean { sum: f64, count: usize },
+    /// The number of distinct items, which cannot be merged because the same
+    /// item may have been counted on both sides. `approximate` is set when
+    /// the count is an estimate.
+    Distinct { count: usize, approximate: bool },
+}
+
+impl WorkerOutput {
+    pub fn merge(self, other: Self) -> Result<Self, MapReduceError> {
+        match (self, other) {
+            (Self::Count(left), Self::Count(right)) => Ok(Self::Count(left + right)),
+            (Self::Float(left), Self::Float(right)) => Ok(Self::Float(left + right)),
+            (
+                Self::Histogram {
+                    mut counts,
+                    approximate,
+                },
+                Self::Histogram {
+                    counts: right_counts,
+                    approximate: right_approximate,
+                },
+            ) => {
+                for (key, count) in right_counts {
+                    *counts.entry(key).or_insert(0) += count;
+                }
+                Ok(Self::Histogram {
+                    counts,
+                    approximate: approximate || right_approximate,
+                })
+            }
+            (Self::Max(left), Self::Max(right)) => Ok(Self::Max(left.max(right))),
+            (
+                Self::Mean { sum, count },
+                Self::Mean {
+                    sum: right_sum,
+                    count: right_count,
+                },
+            ) => Ok(Self::Mean {
+                sum: sum + right_sum,
+                count: count + right_count,
+            }),
+            (left @ Self::Distinct { .. }, Self::Distinct { .. }) => {
+                Err(MapReduceError::UnmergeableOutput(left.kind()))
+            }
+            (left, right) => Err(MapReduceError::MismatchedOutputs(left.kind(), right.kind())),
+        }
+    }
+
+    fn kind(&self) -> &'static str {
+        match self {
+            Self::Count(_) => "count",
+            Self::Float(_) => "float",
+            Self::Histogram { .. } => "histogram",
+            Self::Max(_) => "max",
+            Self::Mean { .. } => "mean",
+            Self::Distinct { .. } => "distinct count",
+        }
+    }
+}
+
+impl fmt::Display for WorkerOutput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Count(count) | Self::Max(count) => write!(f, "{count}"),
+            Self::Distinct { count, approximate } => {
+                write!(f, "{count}")?;
+                write_approximate(f, *approximate)
+            }
+            Self::Float(value) => write!(f, "{value}"),
+            Self::Mean { count: 0, .. } => write!(f, "0"),
+            Self::Mean { sum, count } => write!(f, "{}", sum / *count as f64),
+            Self::Histogram {
+                counts,
+                approximate,
+            } => {
+                let mut entries: Vec<_> = counts.iter().collect();
+                entries.sort();
+                let entries: Vec<String> = entries
+                    .into_iter()
+                    .map(|(key, count)| format!("{key}: {count}"))
+                    .collect();
+                write!(f, "{{{}}}", entries.join(", "))?;
+                write_approximate(f, *approximate)
+            }
+        }
+    }
+}
+
+fn write_approximate(f: &mut fmt::Formatter<'_>, approximate: bool) -> fmt::Result {
+    if approximate {
+        f.write_str(" (approximate)")?;
+    }
+    Ok(())
+}
+
+// Workers whose `usize` or `f64` result is not a sum override
+// `Reducer::output` to pick the variant that merges like they reduce.
+impl From<usize> for WorkerOutput {
+    fn from(count: usize) -> Self {
+        Self::Count(count)
+    }
+}
+
+impl From<f64> for WorkerOutput {
+    fn from(value: f64) -> Self {
+        Self::Float(value)
+    }
+}
+
+impl From<HashMap<String, usize>> for WorkerOutput {
+    fn from(counts: HashMap<String, usize>) -> Self {
+        Self::Histogram {
+            counts,
+            approximate: false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::{
+        LineCountWorker, LongestLineWorker, Mapper, Reducer, StringInputData, SumOfNumbersWorker,
+    };
+
+    fn mapped<W: Mapper>(mut worker: W) -> W {
+        worker.map().unwrap();
+        worker
+    }
+
+    fn input(content: &str) -> Arc<StringInputData> {
+        Arc::new(StringInputData::new(content))
+    }
+
+    #[test]
+    fn outputs_of_the_same_kind_merge_like_their_workers() {
+        let left = mapped(LineCountWorker::new(input("a\nb")));
+        let right = mapped(LineCountWorker::new(input("c")));
+        assert_eq!(
+            left.output().merge(right.output()).unwrap(),
+            WorkerOutput::Count(3)
+        );
+
+        let longest = mapped(LongestLineWorker::new(input("short\nmuch longer")));
+        let shorter = mapped(LongestLineWorker::new(input("tiny")));
+        assert_eq!(
+            longest.output().merge(shorter.output()).unwrap(),
+            WorkerOutput::Max(11)
+        );
+
+        let mean = WorkerOutput::Mean { sum: 3.0, count: 2 }
+            .merge(WorkerOutput::Mean { sum: 6.0, count: 1 })
+            .unwrap();
+        assert_eq!(mean.to_string(), "3");
+    }
+
+    #[test]
+    fn mismatched_and_distinct_outputs_are_rejected() {
+        let count = mapped(LineCountWorker::new(input("a\nb")));
+        let sum = mapped(SumOfNumbersWorker::new(input("1.5")));
+        assert!(matches!(
+            count.output().merge(sum.output()),
+            Err(MapReduceError::MismatchedOutputs("count", "float"))
+        ));
+        let distinct = |count| WorkerOutput::Distinct {
+            count,
+            approximate: false,
+        };
+        assert!(matches!(
+            distinct(2).merge(distinct(3)),
+            Err(MapReduceError::UnmergeableOutput("distinct count"))
+        ));
+    }
+
+    #[test]
+    fn approximate_outputs_say_so_through_a_merge() {
+        let histogram = |counts: &[(&str, usize)], approximate| WorkerOutput::Histogram {
+            counts: counts
+                .iter()
+                .map(|&(key, count)| (key.to_string(), count))
+                .collect(),
+            approximate,
+        };
+        let merged = histogram(&[("a", 1)], false)
+            .merge(histogram(&[("a", 2), ("b", 1)], true))
+            .unwrap();
+        assert_eq!(merged, histogram(&[("a", 3), ("b", 1)], true));
+        assert_eq!(merged.to_string(), "{a: 3, b: 1} (approximate)");
+
+        let estimate = WorkerOutput::Distinct {
+            count: 40,
+            approximate: true,
+        };
+        assert_eq!(estimate.to_string(), "40 (approximate)");
+    }
+}
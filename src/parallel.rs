@@ -0,0 +1,123 @@
+use std::{
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+use rayon::prelude::*;
+
+use crate::{pool::map_catching_panics, MapReduceError, MapReducer, PhaseTimings, RunSummary};
+
+struct Partial<W> {
+    reduced: W,
+    processed: usize,
+    failures: Vec<(PathBuf, MapReduceError)>,
+}
+
+impl<W: MapReducer> Partial<W> {
+    fn identity() -> Self {
+        Self {
+            reduced: W::identity(),
+            processed: 0,
+            failures: Vec::new(),
+        }
+    }
+
+    fn combine(mut self, other: Self) -> Self {
+        self.failures.extend(other.failures);
+        Self {
+            reduced: self.reduced.combine(other.reduced),
+            processed: self.processed + other.processed,
+            failures: self.failures,
+        }
+    }
+}
+
+pub fn run_map_reduce_rayon<W>(
+    workers: Vec<Arc<Mutex<W>>>,
+) -> Result<RunSummary<W::Output>, MapReduceError>
+where
+    W: MapReducer,
+{
+    let started = Instant::now();
+    let mut total = workers
+        .par_iter()
+        .map(|worker| {
+            let outcome = map_catching_panics(worker);
+            let worker = worker.lock();
+            let mut partial = Partial::<W>::identity();
+            match outcome {
+                Ok(()) => {
+                    partial.reduced.reduce(&worker);
+                    partial.processed = 1;
+                }
+                Err(error) => {
+                    let path = worker.input_path().map(PathBuf::from).unwrap_or_default();
+                    partial.failures.push((path, error));
+                }
+            }
+            partial
+        })
+        .reduce(Partial::identity, Partial::combine);
+
+    // Failed inputs are skipped; the job only fails when nothing could be mapped.
+    if total.processed == 0 && !total.failures.is_empty() {
+        let (_, error) = total.failures.swap_remove(0);
+        return Err(error);
+    }
+
+    Ok(RunSummary {
+        result: total.reduced.get_result(),
+        inputs_processed: total.processed,
+        inputs_failed: total.failures.len(),
+        timed_out: 0,
+        cancelled: 0,
+        // Rayon interleaves mapping and reducing, so the phases cannot be
+        // timed separately.
+        timings: PhaseTimings {
+            map_duration: started.elapsed(),
+            reduce_duration: Duration::ZERO,
+            total_duration: started.elapsed(),
+        },
+        failures: total.failures,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        create_workers, map_sequential,
+        test_util::{string_inputs, PanickingInput},
+        Combine, LineCountWorker, Reducer,
+    };
+
+    #[test]
+    fn rayon_matches_the_sequential_result() {
+        let contents: Vec<String> = (0..100).map(|index| "line\n".repeat(index % 7)).collect();
+        let contents: Vec<&str> = contents.iter().map(String::as_str).collect();
+
+        let sequential = create_workers(string_inputs(&contents), LineCountWorker::new);
+        assert!(map_sequential(&sequential, None).iter().all(Result::is_ok));
+        let mut expected = LineCountWorker::identity();
+        for worker in &sequential {
+            expected.reduce(&worker.lock());
+        }
+
+        let workers = create_workers(string_inputs(&contents), LineCountWorker::new);
+        let summary = run_map_reduce_rayon(workers).unwrap();
+        assert_eq!(summary.result, expected.get_result());
+        assert_eq!(summary.inputs_processed, 100);
+    }
+
+    #[test]
+    fn a_panicking_map_is_recorded_as_a_failure() {
+        let mut inputs = string_inputs(&["a\nb", "c"]);
+        inputs.push(Box::new(PanickingInput));
+
+        let summary = run_map_reduce_rayon(create_workers(inputs, LineCountWorker::new)).unwrap();
+        assert_eq!((summary.result, summary.inputs_failed), (3, 1));
+        assert!(matches!(summary.failures[0].1, MapReduceError::Panicked(_)));
+    }
+}
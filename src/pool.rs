@@ -0,0 +1,372 @@
+use std::{
+    any::Any,
+    num::NonZeroUsize,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        mpsc::{self, RecvTimeoutError},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+
+use crate::{MapReduceError, MapReducer, RunOptions};
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+pub type ProgressCallback<'a> = Box<dyn FnMut(usize, usize) + 'a>;
+
+const DEFAULT_QUEUE_CAPACITY: usize = 1024;
+
+pub struct ThreadPool {
+    sender: Option<mpsc::SyncSender<Job>>,
+    threads: Vec<thread::JoinHandle<()>>,
+}
+
+impl ThreadPool {
+    pub fn new(size: usize) -> Self {
+        Self::with_capacity(size, DEFAULT_QUEUE_CAPACITY)
+    }
+
+    /// At most `capacity` jobs wait for a free thread; `execute` blocks until
+    /// there is room for more, so queued jobs cannot grow without bound.
+    pub fn with_capacity(size: usize, capacity: usize) -> Self {
+        assert!(size > 0, "Thread pool size must be greater than zero");
+
+        let (sender, receiver) = mpsc::sync_channel::<Job>(capacity);
+        let receiver = Arc::new(Mutex::new(receiver));
+
+        let threads = (0..size)
+            .map(|_| {
+                let receiver = Arc::clone(&receiver);
+                thread::spawn(move || loop {
+                    let job = receiver.lock().recv();
+                    match job {
+                        Ok(job) => job(),
+                        Err(_) => break,
+                    }
+                })
+            })
+            .collect();
+
+        Self {
+            sender: Some(sender),
+            threads,
+        }
+    }
+
+    pub fn execute<F>(&self, job: F)
+    where
+        F: FnOnce() + Send + 'static,
+    {
+        self.sender
+            .as_ref()
+            .expect("Thread pool has been shut down")
+            .send(Box::new(job))
+            .expect("Thread pool workers have stopped");
+    }
+}
+
+impl Default for ThreadPool {
+    fn default() -> Self {
+        let size = thread::available_parallelism()
+            .map(NonZeroUsize::get)
+            .unwrap_or(1);
+        Self::new(size)
+    }
+}
+
+impl Drop for ThreadPool {
+    fn drop(&mut self) {
+        drop(self.sender.take());
+        for thread in self.threads.drain(..) {
+            thread.join().expect("Thread pool worker panicked");
+        }
+    }
+}
+
+pub fn map_sequential<W>(
+    workers: &[Arc<Mutex<W>>],
+    mut progress: Option<ProgressCallback<'_>>,
+) -> Vec<Result<(), MapReduceError>>
+where
+    W: MapReducer,
+{
+    let total = workers.len();
+
+    workers
+        .iter()
+        .enumerate()
+        .map(|(index, worker)| {
+            let label = describe(worker, index);
+            let started = Instant::now();
+            let outcome = map_catching_panics(worker);
+            log_outcome(&label, &outcome, started);
+            if let Some(progress) = progress.as_mut() {
+                progress(index + 1, total);
+            }
+            outcome
+        })
+        .collect()
+}
+
+pub fn map_parallel<W>(
+    pool: &ThreadPool,
+    workers: &[Arc<Mutex<W>>],
+    options: RunOptions<'_>,
+) -> Vec<Result<(), MapReduceError>>
+where
+    W: MapReducer + 'static,
+{
+    let RunOptions {
+        mut progress,
+        per_worker_timeout,
+        cancel,
+        ..
+    } = options;
+    let (done_sender, done_receiver) = mpsc::channel();
+
+    for (index, worker) in workers.iter().enumerate() {
+        let worker = Arc::clone(worker);
+        let done_sender = done_sender.clone();
+        let cancel = cancel.clone();
+        pool.execute(move || {
+            // Every worker is queued up front, so cancellation is checked as each
+            // one is picked up rather than while dispatching.
+            if cancel.is_some_and(|cancel| cancel.is_cancelled()) {
+                done_sender
+                    .send((index, Err(MapReduceError::Cancelled(None))))
+                    .expect("Failed to report mapped worker");
+                return;
+            }
+
+            let label = describe(&worker, index);
+            let started = Instant::now();
+            let outcome = match per_worker_timeout {
+                Some(timeout) => map_with_timeout(worker, timeout),
+                None => {
+                    let outcome = map_catching_panics(&worker);
+                    // Released before reporting back so that the caller can take
+                    // sole ownership of the worker as soon as it sees the outcome.
+                    drop(worker);
+                    outcome
+                }
+            };
+            log_outcome(&label, &outcome, started);
+            done_sender
+                .send((index, outcome))
+                .expect("Failed to report mapped worker");
+        });
+    }
+    drop(done_sender);
+
+    // Completions are drained on the calling thread, so the progress callback
+    // never has to be shared with the pool.
+    let total = workers.len();
+    let mut outcomes: Vec<Option<Result<(), MapReduceError>>> =
+        workers.iter().map(|_| None).collect();
+    for completed in 1..=total {
+        let (index, outcome) = done_receiver
+            .recv()
+            .expect("Worker thread panicked before finishing map");
+        outcomes[index] = Some(outcome);
+        if let Some(progress) = progress.as_mut() {
+            progress(completed, total);
+        }
+    }
+
+    outcomes.into_iter().flatten().collect()
+}
+
+pub(crate) fn describe<W: MapReducer>(worker: &Mutex<W>, index: usize) -> String {
+    match worker.lock().input_path() {
+        Some(path) => path.display().to_string(),
+        None => format!("input #{index}"),
+    }
+}
+
+pub(crate) fn log_outcome(label: &str, outcome: &Result<(), MapReduceError>, started: Instant) {
+    match outcome {
+        Ok(()) => log::debug!("mapped {label} in {:?}", started.elapsed()),
+        Err(error) => log::warn!("failed to map {label}: {error}"),
+    }
+}
+
+// A panicking map fails only its own worker instead of killing the pool thread
+// and leaving the run waiting on an outcome that never arrives. The worker is
+// left half-mapped, which is safe because failed workers are never reduced.
+pub(crate) fn map_catching_panics<W: MapReducer>(worker: &Mutex<W>) -> Result<(), MapReduceError> {
+    catch_panics(|| worker.lock().map())
+}
+
+pub(crate) fn catch_panics<T>(
+    map: impl FnOnce() -> Result<T, MapReduceError>,
+) -> Result<T, MapReduceError> {
+    panic::catch_unwind(AssertUnwindSafe(map))
+        .unwrap_or_else(|payload| Err(MapReduceError::Panicked(panic_message(payload))))
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => payload.downcast_ref::<&str>().map_or_else(
+            || "unknown panic".to_string(),
+            |message| message.to_string(),
+        ),
+    }
+}
+
+// The map runs on its own detached thread so that an abandoned worker keeps
+// its lock without holding up the pool thread waiting on it.
+fn map_with_timeout<W>(worker: Arc<Mutex<W>>, timeout: Duration) -> Result<(), MapReduceError>
+where
+    W: MapReducer + 'static,
+{
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let _ = sender.send(map_catching_panics(&worker));
+    });
+
+    match receiver.recv_timeout(timeout) {
+        Ok(outcome) => outcome,
+        Err(RecvTimeoutError::Timeout) => Err(MapReduceError::TimedOut(timeout)),
+        Err(RecvTimeoutError::Disconnected) => panic!("Worker thread panicked during map"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicIsize, AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::{
+        create_workers, generate_inputs,
+        test_util::{string_inputs, PanickingInput, TempDir},
+        Combine, LineCountWorker, Reducer,
+    };
+
+    fn line_count_workers(dir: &TempDir) -> Vec<Arc<Mutex<LineCountWorker>>> {
+        create_workers(generate_inputs(dir.path()).unwrap(), LineCountWorker::new)
+    }
+
+    fn total(workers: &[Arc<Mutex<LineCountWorker>>]) -> usize {
+        let mut total = LineCountWorker::identity();
+        for worker in workers {
+            total.reduce(&worker.lock());
+        }
+        total.get_result()
+    }
+
+    #[test]
+    fn parallel_map_matches_sequential_map() {
+        let dir = TempDir::new();
+        for (index, lines) in [3, 1, 4, 1, 5].into_iter().enumerate() {
+            dir.write(&format!("{index}.txt"), "line\n".repeat(lines));
+        }
+
+        let sequential = line_count_workers(&dir);
+        assert!(map_sequential(&sequential, None).iter().all(Result::is_ok));
+        let parallel = line_count_workers(&dir);
+        let outcomes = map_parallel(&ThreadPool::default(), &parallel, RunOptions::new());
+        assert!(outcomes.iter().all(Result::is_ok));
+
+        assert_eq!(total(&parallel), total(&sequential));
+        assert_eq!(total(&parallel), 14);
+    }
+
+    #[test]
+    fn a_pool_of_two_maps_ten_files() {
+        let dir = TempDir::new();
+        for index in 0..10 {
+            dir.write(&format!("{index:02}.txt"), "line\n".repeat(index + 1));
+        }
+
+        let workers = line_count_workers(&dir);
+        let outcomes = map_parallel(&ThreadPool::new(2), &workers, RunOptions::new());
+        assert_eq!(outcomes.len(), 10);
+        assert!(outcomes.iter().all(Result::is_ok));
+        assert_eq!(total(&workers), 55);
+    }
+
+    #[test]
+    fn progress_is_reported_once_per_worker() {
+        let dir = TempDir::new();
+        for index in 0..7 {
+            dir.write(&format!("{index}.txt"), "line\n");
+        }
+
+        let mut sequential_calls = Vec::new();
+        let progress: ProgressCallback =
+            Box::new(|done, total| sequential_calls.push((done, total)));
+        map_sequential(&line_count_workers(&dir), Some(progress));
+        assert_eq!(
+            sequential_calls,
+            (1..=7).map(|done| (done, 7)).collect::<Vec<_>>()
+        );
+
+        let mut parallel_calls = Vec::new();
+        let options = RunOptions::new().progress(|done, total| parallel_calls.push((done, total)));
+        map_parallel(&ThreadPool::new(3), &line_count_workers(&dir), options);
+        assert_eq!(parallel_calls, sequential_calls);
+    }
+
+    #[test]
+    fn a_panicking_map_fails_only_its_worker_in_every_path() {
+        let inputs = || {
+            let mut inputs = string_inputs(&["a\nb", "c"]);
+            inputs.insert(1, Box::new(PanickingInput));
+            create_workers(inputs, LineCountWorker::new)
+        };
+        let check = |outcomes: Vec<Result<(), MapReduceError>>| {
+            assert!(outcomes[0].is_ok() && outcomes[2].is_ok());
+            assert!(matches!(
+                &outcomes[1],
+                Err(MapReduceError::Panicked(message)) if message == "input exploded"
+            ));
+        };
+
+        check(map_sequential(&inputs(), None));
+        let pool = ThreadPool::new(2);
+        check(map_parallel(&pool, &inputs(), RunOptions::new()));
+        let timeout = RunOptions::new().per_worker_timeout(Duration::from_secs(5));
+        check(map_parallel(&pool, &inputs(), timeout));
+
+        // The pool threads survived, so the pool still runs work.
+        let workers = inputs();
+        map_parallel(&pool, &workers, RunOptions::new());
+        assert_eq!(
+            total(&[Arc::clone(&workers[0]), Arc::clone(&workers[2])]),
+            3
+        );
+    }
+
+    #[test]
+    fn the_queue_never_holds_more_than_its_capacity() {
+        const CAPACITY: isize = 4;
+        let pool = ThreadPool::with_capacity(1, CAPACITY as usize);
+        // Jobs handed to the pool but not yet started. Besides the queued ones
+        // this can include the one job the thread has taken but not begun.
+        let queued = Arc::new(AtomicIsize::new(0));
+        let ran = Arc::new(AtomicUsize::new(0));
+        let mut deepest = 0;
+
+        for _ in 0..100 {
+            let (queued_by_job, ran) = (Arc::clone(&queued), Arc::clone(&ran));
+            pool.execute(move || {
+                queued_by_job.fetch_sub(1, Ordering::SeqCst);
+                thread::sleep(Duration::from_millis(1));
+                ran.fetch_add(1, Ordering::SeqCst);
+            });
+            deepest = deepest.max(queued.fetch_add(1, Ordering::SeqCst) + 1);
+        }
+        drop(pool);
+
+        assert_eq!(ran.load(Ordering::SeqCst), 100);
+        assert!(
+            (CAPACITY..=CAPACITY + 1).contains(&deepest),
+            "{deepest} jobs waiting"
+        );
+    }
+}
@@ -0,0 +1,221 @@
+use std::{path::PathBuf, sync::Arc, thread};
+
+use parking_lot::Mutex;
+
+use crate::MapReducer;
+
+// Once mapping is done the caller usually holds the only reference to each
+// worker, so it can be taken out of its mutex and reduced without locking.
+// Workers that are still shared, such as deduplicated inputs, are copied out.
+pub(crate) fn into_owned<W>(workers: Vec<Arc<Mutex<W>>>) -> Vec<W>
+where
+    W: MapReducer,
+{
+    workers.into_iter().map(take).collect()
+}
+
+fn take<W>(worker: Arc<Mutex<W>>) -> W
+where
+    W: MapReducer,
+{
+    Arc::try_unwrap(worker)
+        .map(Mutex::into_inner)
+        .unwrap_or_else(|shared| {
+            let mut copy = W::identity();
+            copy.reduce(&shared.lock());
+            copy
+        })
+}
+
+pub(crate) fn reduce_owned<W>(mapped: Vec<W>) -> W
+where
+    W: MapReducer,
+{
+    let count = mapped.len();
+    let reduced = mapped.into_iter().fold(W::identity(), W::combine);
+    log::debug!("reduced {count} workers");
+    reduced
+}
+
+// Workers are put back into input path order and folded one at a time, so
+// the result does not depend on how inputs were listed or how many there are.
+pub(crate) fn reduce_ordered<W>(mut mapped: Vec<W>) -> W
+where
+    W: MapReducer,
+{
+    mapped.sort_by_cached_key(|worker| worker.input_path().map(PathBuf::from));
+    log::debug!("using ordered reduce for {} workers", mapped.len());
+    reduce_owned(mapped)
+}
+
+pub(crate) const DEFAULT_TREE_REDUCE_THRESHOLD: usize = 16;
+
+// Pairing workers up across threads only pays off once there are enough of
+// them to outweigh the cost of spawning.
+pub(crate) fn reduce_mapped<W>(mapped: Vec<W>, tree_threshold: usize) -> W
+where
+    W: MapReducer,
+{
+    if !uses_tree_reduce(mapped.len(), tree_threshold) {
+        log::debug!("using serial reduce for {} workers", mapped.len());
+        return reduce_owned(mapped);
+    }
+
+    log::debug!("using tree reduce for {} workers", mapped.len());
+    tree_reduce_owned(mapped)
+}
+
+fn uses_tree_reduce(workers: usize, tree_threshold: usize) -> bool {
+    workers >= tree_threshold
+}
+
+pub fn tree_reduce<W>(workers: Vec<Arc<Mutex<W>>>) -> Arc<Mutex<W>>
+where
+    W: MapReducer,
+{
+    // Taking the workers out first means a worker that appears more than once,
+    // as deduplicated inputs do, is never reduced into itself under its lock.
+    Arc::new(Mutex::new(tree_reduce_owned(into_owned(workers))))
+}
+
+fn tree_reduce_owned<W>(mut workers: Vec<W>) -> W
+where
+    W: MapReducer,
+{
+    while workers.len() > 1 {
+        log::debug!("tree-reducing {} workers", workers.len());
+        let mut pairs = Vec::with_capacity(workers.len().div_ceil(2));
+        let mut remaining = workers.into_iter();
+        while let Some(left) = remaining.next() {
+            pairs.push((left, remaining.next()));
+        }
+
+        // An odd trailing worker is carried into the next round untouched.
+        workers = thread::scope(|scope| {
+            let handles: Vec<_> = pairs
+                .into_iter()
+                .map(|(left, right)| match right {
+                    Some(right) => Ok(scope.spawn(move || left.combine(right))),
+                    None => Err(left),
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| match handle {
+                    Ok(handle) => handle.join().expect("Worker panicked during reduce"),
+                    Err(unpaired) => unpaired,
+                })
+                .collect()
+        });
+    }
+
+    workers.pop().unwrap_or_else(W::identity)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        create_workers, create_workers_deduped, map_parallel, run_map_reduce,
+        test_util::{string_inputs, TempDir},
+        Combine, FileInputData, GenericInputData, HeadTailOrder, HeadTailWorker, LineCountWorker,
+        Reducer, RunOptions, ThreadPool,
+    };
+
+    fn mapped(workers: &[Arc<Mutex<LineCountWorker>>]) {
+        let outcomes = map_parallel(&ThreadPool::new(2), workers, RunOptions::new());
+        assert!(outcomes.iter().all(Result::is_ok));
+    }
+
+    #[test]
+    fn tree_reduce_matches_the_serial_fold() {
+        let contents = ["a", "a\nb", "a\nb\nc", "", "a", "a\nb", "a", "a\nb\nc\nd"];
+        let workers = create_workers(string_inputs(&contents), LineCountWorker::new);
+        assert_eq!(workers.len(), 8);
+        mapped(&workers);
+
+        let serial = reduce_owned(into_owned(workers.clone())).get_result();
+        let tree = tree_reduce(workers).lock().get_result();
+        assert_eq!(tree, serial);
+        assert_eq!(tree, 14);
+    }
+
+    #[test]
+    fn tree_reduce_copes_with_shared_workers() {
+        // Identical inputs share one worker, which must not be locked twice.
+        let workers =
+            create_workers_deduped(string_inputs(&["a\nb", "a\nb", "c"]), LineCountWorker::new);
+        assert!(Arc::ptr_eq(&workers[0], &workers[1]));
+        mapped(&workers);
+        assert_eq!(tree_reduce(workers).lock().get_result(), 5);
+    }
+
+    #[test]
+    fn owned_reduce_matches_reducing_through_the_locks() {
+        let workers = create_workers(
+            string_inputs(&["a\nb", "c", "d\ne\nf"]),
+            LineCountWorker::new,
+        );
+        mapped(&workers);
+
+        let mut locked = LineCountWorker::identity();
+        for worker in &workers {
+            locked.reduce(&worker.lock());
+        }
+        let owned = into_owned(workers);
+        assert_eq!(owned.len(), 3);
+        assert_eq!(reduce_owned(owned).get_result(), locked.get_result());
+    }
+
+    #[test]
+    fn both_strategies_agree_and_flip_at_the_threshold() {
+        let contents: Vec<String> = (0..20).map(|index| "line\n".repeat(index)).collect();
+        let contents: Vec<&str> = contents.iter().map(String::as_str).collect();
+        let reduced = |threshold| {
+            let workers = create_workers(string_inputs(&contents), LineCountWorker::new);
+            mapped(&workers);
+            reduce_mapped(into_owned(workers), threshold).get_result()
+        };
+
+        assert_eq!(reduced(usize::MAX), 190);
+        assert_eq!(reduced(1), 190);
+        assert_eq!(reduced(DEFAULT_TREE_REDUCE_THRESHOLD), 190);
+
+        assert!(!uses_tree_reduce(15, 16));
+        assert!(uses_tree_reduce(16, 16));
+        assert!(uses_tree_reduce(17, 16));
+    }
+
+    #[test]
+    fn ordered_reduce_follows_path_order_whatever_the_input_order() {
+        let dir = TempDir::new();
+        let mut paths: Vec<PathBuf> = (0..40)
+            .map(|index| {
+                dir.write(
+                    &format!("{index:02}.txt"),
+                    format!("first {index}\nlast {index}"),
+                )
+            })
+            .collect();
+        // Interleave the paths so neither listing order nor tree pairing
+        // matches path order.
+        paths.sort_by_key(|path| path.to_string_lossy().chars().rev().collect::<String>());
+
+        for _ in 0..5 {
+            let inputs: Vec<Box<dyn GenericInputData<String>>> = paths
+                .iter()
+                .map(|path| {
+                    Box::new(FileInputData::new(path.clone())) as Box<dyn GenericInputData<String>>
+                })
+                .collect();
+            let workers = create_workers(inputs, |input| {
+                HeadTailWorker::new(input, HeadTailOrder::Input)
+            });
+            let summary = run_map_reduce(workers, RunOptions::new().ordered(true)).unwrap();
+            assert_eq!(
+                summary.result,
+                ("first 0".to_string(), "last 39".to_string())
+            );
+        }
+    }
+}
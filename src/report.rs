@@ -0,0 +1,186 @@
+use std::{
+    fs::File,
+    io::{self, BufWriter, Read, Write},
+    ops::Add,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::{Deserialize, Serialize};
+
+use crate::MapReduceError;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FileResult<T = usize> {
+    pub path: PathBuf,
+    pub count: T,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct JobResult<T = usize> {
+    pub total: T,
+    pub per_file: Vec<FileResult<T>>,
+}
+
+impl<T: Add<Output = T>> JobResult<T> {
+    /// Combines the results of two separate runs, e.g. over different
+    /// directories, as if they had been a single run.
+    pub fn merge(mut self, other: Self) -> Self {
+        self.per_file.extend(other.per_file);
+        Self {
+            total: self.total + other.total,
+            per_file: self.per_file,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct PhaseTimings {
+    pub map_duration: Duration,
+    pub reduce_duration: Duration,
+    pub total_duration: Duration,
+}
+
+#[derive(Debug)]
+pub struct RunSummary<T = usize> {
+    pub result: T,
+    pub inputs_processed: usize,
+    pub inputs_failed: usize,
+    pub timed_out: usize,
+    pub cancelled: usize,
+    pub timings: PhaseTimings,
+    pub failures: Vec<(PathBuf, MapReduceError)>,
+}
+
+pub fn write_results_gz(
+    results: &[(PathBuf, usize)],
+    out_path: impl AsRef<Path>,
+) -> Result<(), MapReduceError> {
+    let file = File::create(out_path)?;
+    let encoder = GzEncoder::new(BufWriter::new(file), Compression::default());
+    let encoder = write_results(csv::Writer::from_writer(encoder), results)?;
+    encoder.finish()?.flush()?;
+    Ok(())
+}
+
+pub fn write_results_csv(
+    results: &[(PathBuf, usize)],
+    out_path: impl AsRef<Path>,
+) -> io::Result<()> {
+    write_results(csv::Writer::from_path(out_path)?, results)?;
+    Ok(())
+}
+
+// Hands back the underlying writer, flushed, so a compressed stream can still
+// be finished.
+fn write_results<W: Write>(
+    mut writer: csv::Writer<W>,
+    results: &[(PathBuf, usize)],
+) -> io::Result<W> {
+    writer.write_record(["path", "count"])?;
+    for (path, count) in results {
+        writer.write_record([path.to_string_lossy().as_ref(), &count.to_string()])?;
+    }
+    writer.into_inner().map_err(|error| error.into_error())
+}
+
+pub fn read_results_csv(in_path: impl AsRef<Path>) -> io::Result<Vec<(PathBuf, usize)>> {
+    read_results(csv::Reader::from_path(in_path)?)
+}
+
+pub fn read_results_gz(in_path: impl AsRef<Path>) -> Result<Vec<(PathBuf, usize)>, MapReduceError> {
+    let decoder = GzDecoder::new(File::open(in_path)?);
+    Ok(read_results(csv::Reader::from_reader(decoder))?)
+}
+
+fn read_results<R: Read>(mut reader: csv::Reader<R>) -> io::Result<Vec<(PathBuf, usize)>> {
+    let mut results = Vec::new();
+    for row in reader.deserialize() {
+        let (path, count): (String, usize) = row?;
+        results.push((PathBuf::from(path), count));
+    }
+    Ok(results)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::{
+        create_workers, generate_inputs, run_with_breakdown, test_util::TempDir, LineCountWorker,
+    };
+
+    #[test]
+    fn job_results_round_trip_through_json() {
+        let dir = TempDir::new();
+        let a = dir.write("a.txt", "one\ntwo");
+        let b = dir.write("b.txt", "three");
+
+        let workers = create_workers(generate_inputs(dir.path()).unwrap(), LineCountWorker::new);
+        let result = run_with_breakdown(workers).unwrap();
+        let json = serde_json::to_string_pretty(&result).unwrap();
+
+        let parsed: JobResult = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.total, 3);
+        assert_eq!(
+            parsed.per_file,
+            [
+                FileResult { path: a, count: 2 },
+                FileResult { path: b, count: 1 }
+            ]
+        );
+    }
+
+    #[test]
+    fn merged_runs_match_one_run_over_both_dirs() {
+        let dir = TempDir::new();
+        dir.write("logs/a.txt", "one\ntwo");
+        dir.write("logs/b.txt", "three");
+        dir.write("archive/c.txt", "four\nfive\nsix");
+        let (logs, archive) = (dir.path().join("logs"), dir.path().join("archive"));
+
+        let run = |inputs| run_with_breakdown(create_workers(inputs, LineCountWorker::new));
+        let first = run(generate_inputs(&logs).unwrap()).unwrap();
+        let second = run(generate_inputs(&archive).unwrap()).unwrap();
+        // Each run's result goes through JSON, as if written out by another process.
+        let second: JobResult =
+            serde_json::from_str(&serde_json::to_string(&second).unwrap()).unwrap();
+
+        let mut union = generate_inputs(&logs).unwrap();
+        union.extend(generate_inputs(&archive).unwrap());
+        assert_eq!(first.merge(second), run(union).unwrap());
+    }
+
+    fn sample_results() -> Vec<(PathBuf, usize)> {
+        vec![
+            (PathBuf::from("logs/a.txt"), 3),
+            (PathBuf::from("logs/with, comma.txt"), 0),
+            (PathBuf::from("logs/\"quoted\".txt"), 12),
+        ]
+    }
+
+    #[test]
+    fn gzipped_results_round_trip() {
+        let dir = TempDir::new();
+        let out_path = dir.path().join("results.csv.gz");
+        let results = sample_results();
+
+        write_results_gz(&results, &out_path).unwrap();
+        assert_eq!(read_results_gz(&out_path).unwrap(), results);
+    }
+
+    #[test]
+    fn csv_results_round_trip_with_quoted_paths() {
+        let dir = TempDir::new();
+        let out_path = dir.path().join("results.csv");
+        let results = sample_results();
+
+        write_results_csv(&results, &out_path).unwrap();
+        let csv = fs::read_to_string(&out_path).unwrap();
+        assert!(csv.starts_with("path,count\n"));
+        assert!(csv.contains("\"logs/with, comma.txt\",0"));
+        assert_eq!(read_results_csv(&out_path).unwrap(), results);
+    }
+}
@@ -0,0 +1,149 @@
+use std::sync::Arc;
+
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::{run_map_reduce, MapReduceError, MapReducer, RunOptions};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SampleEstimate {
+    pub sampled_total: usize,
+    pub estimate: usize,
+    pub inputs_sampled: usize,
+    pub inputs_total: usize,
+}
+
+impl SampleEstimate {
+    /// Extrapolates `sampled_total` from `inputs_sampled` inputs to all
+    /// `inputs_total` of them.
+    pub fn new(sampled_total: usize, inputs_sampled: usize, inputs_total: usize) -> Self {
+        let estimate = match inputs_sampled {
+            0 => 0,
+            _ => (sampled_total as u128 * inputs_total as u128 / inputs_sampled as u128) as usize,
+        };
+        Self {
+            sampled_total,
+            estimate,
+            inputs_sampled,
+            inputs_total,
+        }
+    }
+}
+
+/// Picks `fraction` of `items`, rounded up, keeping them in their original
+/// order. The same seed always picks the same items.
+pub fn sample_inputs<T>(items: Vec<T>, fraction: f64, seed: u64) -> Result<Vec<T>, MapReduceError> {
+    if !(fraction > 0.0 && fraction <= 1.0) {
+        return Err(MapReduceError::InvalidSampleFraction(fraction));
+    }
+    let count = ((items.len() as f64 * fraction).ceil() as usize).min(items.len());
+
+    // A partial Fisher-Yates shuffle moves a random choice into each of the
+    // first `count` slots.
+    let mut indices: Vec<usize> = (0..items.len()).collect();
+    let mut rng = SplitMix64(seed);
+    for slot in 0..count {
+        let remaining = (items.len() - slot) as u64;
+        indices.swap(slot, slot + (rng.next() % remaining) as usize);
+    }
+    let mut chosen = vec![false; items.len()];
+    for &index in &indices[..count] {
+        chosen[index] = true;
+    }
+
+    Ok(items
+        .into_iter()
+        .zip(chosen)
+        .filter_map(|(item, chosen)| chosen.then_some(item))
+        .collect())
+}
+
+/// Maps and reduces a seeded sample of `workers` and scales the result up by
+/// the number of inputs that were left out.
+pub fn run_map_reduce_sampled<W>(
+    workers: Vec<Arc<Mutex<W>>>,
+    fraction: f64,
+    seed: u64,
+) -> Result<SampleEstimate, MapReduceError>
+where
+    W: MapReducer<Output = usize> + 'static,
+{
+    let inputs_total = workers.len();
+    let sampled = sample_inputs(workers, fraction, seed)?;
+    let summary = run_map_reduce(sampled, RunOptions::new())?;
+    Ok(SampleEstimate::new(
+        summary.result,
+        summary.inputs_processed,
+        inputs_total,
+    ))
+}
+
+// A small generator of our own, rather than a dependency, so that a seed picks
+// the same sample on every platform and with every version of this crate.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{create_workers, generate_inputs, test_util::TempDir, LineCountWorker};
+
+    #[test]
+    fn a_fixed_seed_picks_the_same_sample() {
+        let items: Vec<usize> = (0..100).collect();
+        let sample = sample_inputs(items.clone(), 0.25, 7).unwrap();
+        assert_eq!(sample.len(), 25);
+        assert!(sample.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(sample_inputs(items.clone(), 0.25, 7).unwrap(), sample);
+        assert_ne!(sample_inputs(items.clone(), 0.25, 8).unwrap(), sample);
+
+        assert_eq!(sample_inputs(items.clone(), 1.0, 7).unwrap(), items);
+        for fraction in [0.0, -0.5, 1.5, f64::NAN] {
+            assert!(matches!(
+                sample_inputs(items.clone(), fraction, 7),
+                Err(MapReduceError::InvalidSampleFraction(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn a_sampled_run_extrapolates_the_total() {
+        let dir = TempDir::new();
+        // 40 files of 10 to 50 lines, 1,200 lines in all.
+        for index in 0..40 {
+            dir.write(
+                &format!("{index:02}.txt"),
+                "line\n".repeat(10 + index % 5 * 10),
+            );
+        }
+        let workers = || create_workers(generate_inputs(dir.path()).unwrap(), LineCountWorker::new);
+
+        let estimate = run_map_reduce_sampled(workers(), 0.25, 42).unwrap();
+        assert_eq!((estimate.inputs_sampled, estimate.inputs_total), (10, 40));
+        assert_eq!(estimate.estimate, estimate.sampled_total * 4);
+        assert!(
+            (800..=1600).contains(&estimate.estimate),
+            "estimated {} lines",
+            estimate.estimate
+        );
+        assert_eq!(
+            run_map_reduce_sampled(workers(), 0.25, 42).unwrap(),
+            estimate
+        );
+
+        let everything = run_map_reduce_sampled(workers(), 1.0, 42).unwrap();
+        assert_eq!(
+            (everything.sampled_total, everything.estimate),
+            (1200, 1200)
+        );
+    }
+}
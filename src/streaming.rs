@@ -0,0 +1,109 @@
+use std::{
+    path::PathBuf,
+    sync::{
+        mpsc::{self, Receiver},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::Instant,
+};
+
+use parking_lot::Mutex;
+
+use crate::{
+    pool::{describe, log_outcome, map_catching_panics},
+    reduce::{into_owned, reduce_owned},
+    MapReducer, ThreadPool,
+};
+
+pub type StreamedResults<T> = Receiver<(PathBuf, T)>;
+
+pub fn run_map_reduce_streaming<W>(
+    workers: Vec<Arc<Mutex<W>>>,
+) -> (StreamedResults<W::Output>, JoinHandle<W::Output>)
+where
+    W: MapReducer + 'static,
+    W::Output: Send + 'static,
+{
+    let (result_sender, result_receiver) = mpsc::channel();
+
+    let handle = thread::spawn(move || {
+        let pool = ThreadPool::default();
+        let (mapped_sender, mapped_receiver) = mpsc::channel();
+
+        for (index, worker) in workers.into_iter().enumerate() {
+            let result_sender = result_sender.clone();
+            let mapped_sender = mapped_sender.clone();
+            pool.execute(move || {
+                let label = describe(&worker, index);
+                let started = Instant::now();
+                let outcome = map_catching_panics(&worker);
+                log_outcome(&label, &outcome, started);
+                if outcome.is_err() {
+                    return;
+                }
+
+                let result = {
+                    let worker = worker.lock();
+                    let path = worker.input_path().map(PathBuf::from).unwrap_or_default();
+                    (path, worker.get_result())
+                };
+                // The caller may only be interested in the final total and
+                // drop the receiver early.
+                let _ = result_sender.send(result);
+                mapped_sender
+                    .send((index, worker))
+                    .expect("Failed to report mapped worker");
+            });
+        }
+        drop(result_sender);
+        drop(mapped_sender);
+
+        // Workers finish in any order; reducing in input order keeps the total
+        // identical to `run_map_reduce`.
+        let mut mapped: Vec<(usize, Arc<Mutex<W>>)> = mapped_receiver.iter().collect();
+        mapped.sort_by_key(|(index, _)| *index);
+        let mapped: Vec<Arc<Mutex<W>>> = mapped.into_iter().map(|(_, worker)| worker).collect();
+        reduce_owned(into_owned(mapped)).get_result()
+    });
+
+    (result_receiver, handle)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::{
+        create_workers, generate_inputs,
+        test_util::{string_inputs, PanickingInput, TempDir},
+        LineCountWorker,
+    };
+
+    #[test]
+    fn streams_one_result_per_input_then_the_total() {
+        let dir = TempDir::new();
+        let a = dir.write("a.txt", "one\ntwo");
+        let b = dir.write("b.txt", "three");
+        let c = dir.write("c.txt", "four\nfive\nsix");
+
+        let workers = create_workers(generate_inputs(dir.path()).unwrap(), LineCountWorker::new);
+        let (results, total) = run_map_reduce_streaming(workers);
+        let streamed: HashMap<PathBuf, usize> = results.iter().collect();
+
+        assert_eq!(streamed, HashMap::from([(a, 2), (b, 1), (c, 3)]));
+        assert_eq!(total.join().unwrap(), 6);
+    }
+
+    #[test]
+    fn a_panicking_map_is_left_out_of_the_stream_and_total() {
+        let mut inputs = string_inputs(&["a\nb", "c"]);
+        inputs.push(Box::new(PanickingInput));
+
+        let (results, total) =
+            run_map_reduce_streaming(create_workers(inputs, LineCountWorker::new));
+        assert_eq!(results.iter().count(), 2);
+        assert_eq!(total.join().unwrap(), 3);
+    }
+}
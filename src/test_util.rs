@@ -0,0 +1,115 @@
+use std::{
+    fs,
+    io::{BufRead, BufReader, Write},
+    net::TcpListener,
+    path::{Path, PathBuf},
+    process,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+};
+
+use crate::{GenericInputData, MapReduceError, StringInputData};
+
+static NEXT_DIR: AtomicUsize = AtomicUsize::new(0);
+
+// A fresh directory under the system temp dir, removed again on drop, so tests
+// running in parallel never see each other's files.
+pub(crate) struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    pub(crate) fn new() -> Self {
+        let id = NEXT_DIR.fetch_add(1, Ordering::Relaxed);
+        let path = std::env::temp_dir().join(format!("rs_map_reduce-{}-{id}", process::id()));
+        fs::create_dir_all(&path).expect("could not create temp dir");
+        Self { path }
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub(crate) fn write(&self, name: &str, contents: impl AsRef<[u8]>) -> PathBuf {
+        let path = self.path.join(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).expect("could not create temp subdir");
+        }
+        fs::write(&path, contents).expect("could not write temp file");
+        path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+pub(crate) fn string_inputs(contents: &[&str]) -> Vec<Box<dyn GenericInputData<String>>> {
+    contents
+        .iter()
+        .map(|content| {
+            Box::new(StringInputData::new(*content)) as Box<dyn GenericInputData<String>>
+        })
+        .collect()
+}
+
+// In-memory content that counts how often it has been read.
+pub(crate) struct CountingInput {
+    content: String,
+    reads: Arc<AtomicUsize>,
+}
+
+impl CountingInput {
+    pub(crate) fn new(content: &str) -> (Self, Arc<AtomicUsize>) {
+        let reads = Arc::new(AtomicUsize::new(0));
+        let input = Self {
+            content: content.to_string(),
+            reads: Arc::clone(&reads),
+        };
+        (input, reads)
+    }
+}
+
+impl GenericInputData<String> for CountingInput {
+    fn read(&self) -> Result<String, MapReduceError> {
+        self.reads.fetch_add(1, Ordering::SeqCst);
+        Ok(self.content.clone())
+    }
+}
+
+// Panics when read, standing in for a worker whose map panics.
+pub(crate) struct PanickingInput;
+
+impl GenericInputData<String> for PanickingInput {
+    fn read(&self) -> Result<String, MapReduceError> {
+        panic!("input exploded");
+    }
+}
+
+// Answers one request per response, in order, on a local port and returns
+// the URL to request.
+pub(crate) fn serve(responses: Vec<(u16, &'static str)>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").expect("could not bind mock server");
+    let url = format!("http://{}/data.txt", listener.local_addr().unwrap());
+    thread::spawn(move || {
+        for (status, body) in responses {
+            let (mut stream, _) = listener.accept().expect("mock server accept failed");
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut line = String::new();
+            while reader.read_line(&mut line).is_ok_and(|read| read > 2) {
+                line.clear();
+            }
+            let response = format!(
+                "HTTP/1.1 {status} Mock\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{body}",
+                body.len()
+            );
+            let _ = stream.write_all(response.as_bytes());
+        }
+    });
+    url
+}
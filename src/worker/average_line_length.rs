@@ -0,0 +1,98 @@
+use std::{path::Path, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Combine, GenericInputData, MapReduceError, MapReducer, Mapper, Reducer, StringInputData,
+    WorkerOutput,
+};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct AverageLineLengthWorker {
+    #[serde(skip, default = "super::no_input")]
+    input_data: Arc<dyn GenericInputData<String>>,
+    total_chars: usize,
+    total_lines: usize,
+}
+
+impl AverageLineLengthWorker {
+    pub fn new(input_data: Arc<dyn GenericInputData<String>>) -> Self {
+        Self {
+            input_data,
+            total_chars: 0,
+            total_lines: 0,
+        }
+    }
+}
+
+impl Mapper for AverageLineLengthWorker {
+    fn map(&mut self) -> Result<(), MapReduceError> {
+        let data = self.input_data.read()?;
+        self.total_chars = 0;
+        self.total_lines = 0;
+        for line in data.lines() {
+            self.total_chars += line.chars().count();
+            self.total_lines += 1;
+        }
+        Ok(())
+    }
+
+    fn input_path(&self) -> Option<&Path> {
+        self.input_data.path()
+    }
+}
+
+impl Reducer for AverageLineLengthWorker {
+    type Output = f64;
+
+    fn reduce(&mut self, other: &Self) {
+        self.total_chars += other.total_chars;
+        self.total_lines += other.total_lines;
+    }
+
+    fn get_result(&self) -> Self::Output {
+        if self.total_lines == 0 {
+            return 0.0;
+        }
+        self.total_chars as f64 / self.total_lines as f64
+    }
+
+    fn output(&self) -> WorkerOutput {
+        WorkerOutput::Mean {
+            sum: self.total_chars as f64,
+            count: self.total_lines,
+        }
+    }
+}
+
+impl Combine for AverageLineLengthWorker {
+    fn identity() -> Self {
+        Self::new(Arc::new(StringInputData::default()))
+    }
+}
+
+impl MapReducer for AverageLineLengthWorker {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{run, test_util::string_inputs};
+
+    #[test]
+    fn averages_over_all_lines_rather_than_per_input() {
+        // Averaging the two inputs' own averages would give 4.5.
+        let average = run(
+            string_inputs(&["a\nb", "cccccccc"]),
+            AverageLineLengthWorker::new,
+        );
+        assert_eq!(average.unwrap(), 10.0 / 3.0);
+    }
+
+    #[test]
+    fn no_lines_average_to_zero() {
+        assert_eq!(
+            run(string_inputs(&[""]), AverageLineLengthWorker::new).unwrap(),
+            0.0
+        );
+    }
+}
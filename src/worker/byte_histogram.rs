@@ -0,0 +1,83 @@
+use std::{collections::BTreeMap, path::Path, sync::Arc};
+
+use crate::{
+    Combine, GenericInputData, MapReduceError, MapReducer, Mapper, Reducer, StringInputData,
+};
+
+#[derive(Clone)]
+pub struct ByteHistogramWorker {
+    input_data: Arc<dyn GenericInputData<Vec<u8>>>,
+    counts: [usize; 256],
+}
+
+impl ByteHistogramWorker {
+    pub fn new(input_data: Arc<dyn GenericInputData<Vec<u8>>>) -> Self {
+        Self {
+            input_data,
+            counts: [0; 256],
+        }
+    }
+}
+
+impl Mapper for ByteHistogramWorker {
+    fn map(&mut self) -> Result<(), MapReduceError> {
+        let data = self.input_data.read()?;
+        self.counts = [0; 256];
+        for &byte in &data {
+            self.counts[usize::from(byte)] += 1;
+        }
+        Ok(())
+    }
+
+    fn input_path(&self) -> Option<&Path> {
+        self.input_data.path()
+    }
+}
+
+impl Reducer for ByteHistogramWorker {
+    type Output = BTreeMap<u8, usize>;
+
+    fn reduce(&mut self, other: &Self) {
+        for (count, other) in self.counts.iter_mut().zip(&other.counts) {
+            *count += other;
+        }
+    }
+
+    fn get_result(&self) -> Self::Output {
+        (0..=u8::MAX)
+            .zip(self.counts)
+            .filter(|&(_, count)| count > 0)
+            .collect()
+    }
+}
+
+impl Combine for ByteHistogramWorker {
+    fn identity() -> Self {
+        Self::new(Arc::new(StringInputData::default()))
+    }
+}
+
+impl MapReducer for ByteHistogramWorker {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_inputs_binary, run, test_util::TempDir};
+
+    #[test]
+    fn counts_every_byte_of_binary_files() {
+        let dir = TempDir::new();
+        dir.write("a.bin", [0x00, 0xff, 0xff, 0x0a]);
+        dir.write("b.bin", [0xff, 0x80, 0x00]);
+
+        let histogram = run(
+            generate_inputs_binary(dir.path()).unwrap(),
+            ByteHistogramWorker::new,
+        )
+        .unwrap();
+        assert_eq!(
+            histogram,
+            BTreeMap::from([(0x00, 2), (0x0a, 1), (0x80, 1), (0xff, 3)])
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use std::{path::Path, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Combine, GenericInputData, MapReduceError, MapReducer, Mapper, Reducer, StringInputData,
+};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct ByteSizeWorker {
+    #[serde(skip, default = "super::no_input")]
+    input_data: Arc<dyn GenericInputData<String>>,
+    result: usize,
+}
+
+impl ByteSizeWorker {
+    pub fn new(input_data: Arc<dyn GenericInputData<String>>) -> Self {
+        Self {
+            input_data,
+            result: 0,
+        }
+    }
+}
+
+impl Mapper for ByteSizeWorker {
+    fn map(&mut self) -> Result<(), MapReduceError> {
+        let data = self.input_data.read()?;
+        self.result = data.len();
+        Ok(())
+    }
+
+    fn input_path(&self) -> Option<&Path> {
+        self.input_data.path()
+    }
+}
+
+impl Reducer for ByteSizeWorker {
+    type Output = usize;
+
+    fn reduce(&mut self, other: &Self) {
+        self.result += other.result;
+    }
+
+    fn get_result(&self) -> Self::Output {
+        self.result
+    }
+}
+
+impl Combine for ByteSizeWorker {
+    fn identity() -> Self {
+        Self::new(Arc::new(StringInputData::default()))
+    }
+}
+
+impl MapReducer for ByteSizeWorker {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{create_workers, generate_inputs, run_map_reduce, test_util::TempDir, RunOptions};
+
+    #[test]
+    fn sums_the_bytes_of_every_input() {
+        let dir = TempDir::new();
+        dir.write("a.txt", "0123456789");
+        dir.write("b.txt", "héllo\n");
+
+        let workers = create_workers(generate_inputs(dir.path()).unwrap(), ByteSizeWorker::new);
+        assert_eq!(
+            run_map_reduce(workers, RunOptions::new()).unwrap().result,
+            17
+        );
+    }
+}
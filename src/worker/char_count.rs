@@ -0,0 +1,88 @@
+use std::{path::Path, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Combine, GenericInputData, MapReduceError, MapReducer, Mapper, Reducer, StringInputData,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CountMode {
+    Bytes,
+    Chars,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CharCountWorker {
+    #[serde(skip, default = "super::no_input")]
+    input_data: Arc<dyn GenericInputData<String>>,
+    by: CountMode,
+    result: usize,
+}
+
+impl CharCountWorker {
+    pub fn new(input_data: Arc<dyn GenericInputData<String>>, by: CountMode) -> Self {
+        Self {
+            input_data,
+            by,
+            result: 0,
+        }
+    }
+}
+
+impl Mapper for CharCountWorker {
+    fn map(&mut self) -> Result<(), MapReduceError> {
+        let data = self.input_data.read()?;
+        self.result = match self.by {
+            CountMode::Bytes => data.len(),
+            CountMode::Chars => data.chars().count(),
+        };
+        Ok(())
+    }
+
+    fn input_path(&self) -> Option<&Path> {
+        self.input_data.path()
+    }
+}
+
+impl Reducer for CharCountWorker {
+    type Output = usize;
+
+    fn reduce(&mut self, other: &Self) {
+        self.result += other.result;
+    }
+
+    fn get_result(&self) -> Self::Output {
+        self.result
+    }
+}
+
+impl Combine for CharCountWorker {
+    fn identity() -> Self {
+        Self::new(Arc::new(StringInputData::default()), CountMode::Chars)
+    }
+}
+
+impl MapReducer for CharCountWorker {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{run, test_util::TempDir, FileInputData};
+
+    fn count(content: &str, by: CountMode) -> usize {
+        let dir = TempDir::new();
+        let path = dir.write("multibyte.txt", content);
+        let input_list: Vec<Box<dyn GenericInputData<String>>> =
+            vec![Box::new(FileInputData::new(path))];
+        run(input_list, |input| CharCountWorker::new(input, by)).unwrap()
+    }
+
+    #[test]
+    fn bytes_and_chars_differ_for_multibyte_text() {
+        assert_eq!(count("héllo", CountMode::Bytes), 6);
+        assert_eq!(count("héllo", CountMode::Chars), 5);
+        assert_eq!(count("hello", CountMode::Bytes), 5);
+        assert_eq!(count("hello", CountMode::Chars), 5);
+    }
+}
@@ -0,0 +1,72 @@
+use std::{collections::BTreeMap, path::Path, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Combine, GenericInputData, MapReduceError, MapReducer, Mapper, Reducer, StringInputData,
+};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct CharFrequencyWorker {
+    #[serde(skip, default = "super::no_input")]
+    input_data: Arc<dyn GenericInputData<String>>,
+    result: BTreeMap<char, usize>,
+}
+
+impl CharFrequencyWorker {
+    pub fn new(input_data: Arc<dyn GenericInputData<String>>) -> Self {
+        Self {
+            input_data,
+            result: BTreeMap::new(),
+        }
+    }
+}
+
+impl Mapper for CharFrequencyWorker {
+    fn map(&mut self) -> Result<(), MapReduceError> {
+        let data = self.input_data.read()?;
+        self.result.clear();
+        for c in data.chars().filter(|c| !c.is_whitespace()) {
+            *self.result.entry(c).or_insert(0) += 1;
+        }
+        Ok(())
+    }
+
+    fn input_path(&self) -> Option<&Path> {
+        self.input_data.path()
+    }
+}
+
+impl Reducer for CharFrequencyWorker {
+    type Output = BTreeMap<char, usize>;
+
+    fn reduce(&mut self, other: &Self) {
+        for (&c, &count) in &other.result {
+            *self.result.entry(c).or_insert(0) += count;
+        }
+    }
+
+    fn get_result(&self) -> Self::Output {
+        self.result.clone()
+    }
+}
+
+impl Combine for CharFrequencyWorker {
+    fn identity() -> Self {
+        Self::new(Arc::new(StringInputData::default()))
+    }
+}
+
+impl MapReducer for CharFrequencyWorker {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{run, test_util::string_inputs};
+
+    #[test]
+    fn counts_non_whitespace_characters_across_inputs() {
+        let frequencies = run(string_inputs(&["abba", "b c\n"]), CharFrequencyWorker::new).unwrap();
+        assert_eq!(frequencies, BTreeMap::from([('a', 2), ('b', 3), ('c', 1)]));
+    }
+}
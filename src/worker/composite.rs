@@ -0,0 +1,111 @@
+use std::{path::Path, sync::Arc};
+
+use crate::{
+    CachedInputData, Combine, GenericInputData, MapReduceError, MapReducer, Mapper, Reducer,
+    StringInputData,
+};
+
+#[derive(Clone)]
+pub struct CompositeWorker<A, B> {
+    input_data: Arc<CachedInputData>,
+    first: A,
+    second: B,
+}
+
+impl<A, B> CompositeWorker<A, B>
+where
+    A: MapReducer,
+    B: MapReducer,
+{
+    pub fn new<F, G>(
+        input_data: Arc<dyn GenericInputData<String>>,
+        make_first: F,
+        make_second: G,
+    ) -> Self
+    where
+        F: FnOnce(Arc<dyn GenericInputData<String>>) -> A,
+        G: FnOnce(Arc<dyn GenericInputData<String>>) -> B,
+    {
+        let input_data = Arc::new(CachedInputData::new(input_data));
+        Self {
+            first: make_first(input_data.clone()),
+            second: make_second(input_data.clone()),
+            input_data,
+        }
+    }
+}
+
+impl<A, B> Mapper for CompositeWorker<A, B>
+where
+    A: MapReducer,
+    B: MapReducer,
+{
+    // Both inner workers read through the shared cache, so the underlying
+    // input is only read once. The cache is dropped again once both are done.
+    fn map(&mut self) -> Result<(), MapReduceError> {
+        let outcome = self.first.map().and_then(|()| self.second.map());
+        self.input_data.clear();
+        outcome
+    }
+
+    fn input_path(&self) -> Option<&Path> {
+        self.input_data.path()
+    }
+}
+
+impl<A, B> Reducer for CompositeWorker<A, B>
+where
+    A: MapReducer,
+    B: MapReducer,
+{
+    type Output = (A::Output, B::Output);
+
+    fn reduce(&mut self, other: &Self) {
+        self.first.reduce(&other.first);
+        self.second.reduce(&other.second);
+    }
+
+    fn get_result(&self) -> Self::Output {
+        (self.first.get_result(), self.second.get_result())
+    }
+}
+
+impl<A, B> Combine for CompositeWorker<A, B>
+where
+    A: MapReducer,
+    B: MapReducer,
+{
+    fn identity() -> Self {
+        Self {
+            input_data: Arc::new(CachedInputData::new(Arc::new(StringInputData::default()))),
+            first: A::identity(),
+            second: B::identity(),
+        }
+    }
+}
+
+impl<A, B> MapReducer for CompositeWorker<A, B>
+where
+    A: MapReducer,
+    B: MapReducer,
+{
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::Ordering;
+
+    use super::*;
+    use crate::{test_util::CountingInput, LineCountWorker, WordCountWorker};
+
+    #[test]
+    fn both_counts_come_from_one_read() {
+        let (input, reads) = CountingInput::new("the quick\nbrown fox\njumps");
+        let mut worker =
+            CompositeWorker::new(Arc::new(input), LineCountWorker::new, WordCountWorker::new);
+
+        worker.map().unwrap();
+        assert_eq!(worker.get_result(), (3, 5));
+        assert_eq!(reads.load(Ordering::SeqCst), 1);
+    }
+}
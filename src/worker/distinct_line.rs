@@ -0,0 +1,258 @@
+use std::{collections::HashSet, mem, path::Path, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use super::hyper_log_log::Sketch;
+use crate::{
+    Combine, ContentHasher, GenericInputData, MapReduceError, MapReducer, Mapper, Reducer,
+    StringInputData, WorkerOutput,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DistinctLines {
+    pub count: usize,
+    /// Set once the lines outgrew the memory limit and `count` became an
+    /// estimate.
+    pub approximate: bool,
+}
+
+impl From<DistinctLines> for WorkerOutput {
+    fn from(distinct: DistinctLines) -> Self {
+        Self::Distinct {
+            count: distinct.count,
+            approximate: distinct.approximate,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+enum Lines {
+    Exact {
+        lines: HashSet<String>,
+        bytes: usize,
+    },
+    Approximate(Sketch),
+}
+
+impl Default for Lines {
+    fn default() -> Self {
+        Self::Exact {
+            lines: HashSet::new(),
+            bytes: 0,
+        }
+    }
+}
+
+// Every distinct line is held in memory until the final reduce unless a memory
+// limit is set, past which the lines are folded into a HyperLogLog sketch.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DistinctLineWorker {
+    #[serde(skip, default = "super::no_input")]
+    input_data: Arc<dyn GenericInputData<String>>,
+    max_memory_bytes: Option<usize>,
+    hasher: ContentHasher,
+    lines: Lines,
+}
+
+impl DistinctLineWorker {
+    pub fn new(input_data: Arc<dyn GenericInputData<String>>) -> Self {
+        Self {
+            input_data,
+            max_memory_bytes: None,
+            hasher: ContentHasher::default(),
+            lines: Lines::default(),
+        }
+    }
+
+    /// The limit is checked against an estimate of the memory the lines take
+    /// up, not against what is actually allocated.
+    pub fn with_max_memory(
+        input_data: Arc<dyn GenericInputData<String>>,
+        max_memory_bytes: usize,
+    ) -> Self {
+        Self {
+            max_memory_bytes: Some(max_memory_bytes),
+            ..Self::new(input_data)
+        }
+    }
+
+    /// Like [`with_max_memory`](Self::with_max_memory), with the estimate past
+    /// the limit hashed by `hasher`. Every worker in a run has to use the same.
+    pub fn with_hasher(
+        input_data: Arc<dyn GenericInputData<String>>,
+        max_memory_bytes: usize,
+        hasher: ContentHasher,
+    ) -> Self {
+        Self {
+            hasher,
+            ..Self::with_max_memory(input_data, max_memory_bytes)
+        }
+    }
+
+    fn insert(&mut self, line: &str) {
+        match &mut self.lines {
+            Lines::Exact { lines, bytes } => {
+                if !lines.contains(line) {
+                    *bytes += mem::size_of::<String>() + line.len();
+                    lines.insert(line.to_owned());
+                }
+                if self.max_memory_bytes.is_some_and(|max| *bytes > max) {
+                    self.make_approximate();
+                }
+            }
+            Lines::Approximate(sketch) => sketch.insert(line),
+        }
+    }
+
+    fn make_approximate(&mut self) {
+        if let Lines::Exact { lines, bytes } = &self.lines {
+            log::warn!("distinct lines exceeded {bytes} bytes, switching to an estimate");
+            let mut sketch = Sketch::new(self.hasher);
+            for line in lines {
+                sketch.insert(line);
+            }
+            self.lines = Lines::Approximate(sketch);
+        }
+    }
+}
+
+impl Mapper for DistinctLineWorker {
+    fn map(&mut self) -> Result<(), MapReduceError> {
+        let data = self.input_data.read()?;
+        self.lines = Lines::default();
+        for line in data.lines() {
+            self.insert(line);
+        }
+        Ok(())
+    }
+
+    fn input_path(&self) -> Option<&Path> {
+        self.input_data.path()
+    }
+}
+
+impl Reducer for DistinctLineWorker {
+    type Output = DistinctLines;
+
+    fn reduce(&mut self, other: &Self) {
+        // The identity has no limit or hasher of its own, so it takes on the
+        // other's.
+        if self.max_memory_bytes.is_none() {
+            self.max_memory_bytes = other.max_memory_bytes;
+            self.hasher = other.hasher;
+        }
+        match &other.lines {
+            Lines::Exact { lines, .. } => {
+                for line in lines {
+                    self.insert(line);
+                }
+            }
+            Lines::Approximate(other_sketch) => {
+                self.make_approximate();
+                if let Lines::Approximate(sketch) = &mut self.lines {
+                    sketch.merge(other_sketch);
+                }
+            }
+        }
+    }
+
+    fn get_result(&self) -> Self::Output {
+        match &self.lines {
+            Lines::Exact { lines, .. } => DistinctLines {
+                count: lines.len(),
+                approximate: false,
+            },
+            Lines::Approximate(sketch) => DistinctLines {
+                count: sketch.estimate(),
+                approximate: true,
+            },
+        }
+    }
+}
+
+impl Combine for DistinctLineWorker {
+    fn identity() -> Self {
+        Self::new(Arc::new(StringInputData::default()))
+    }
+}
+
+impl MapReducer for DistinctLineWorker {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{run, test_util::string_inputs};
+
+    #[test]
+    fn overlapping_lines_are_counted_once() {
+        let inputs = string_inputs(&["red\ngreen\nblue\nred", "blue\nyellow\ngreen"]);
+        let distinct = run(inputs, DistinctLineWorker::new).unwrap();
+        assert_eq!(
+            distinct,
+            DistinctLines {
+                count: 4,
+                approximate: false
+            }
+        );
+    }
+
+    #[test]
+    fn a_tiny_memory_limit_switches_to_an_estimate() {
+        let contents: Vec<String> = (0..4)
+            .map(|part| {
+                (0..5_000)
+                    .map(|line| format!("part {part} line {line}\n"))
+                    .collect()
+            })
+            .collect();
+        let contents: Vec<&str> = contents.iter().map(String::as_str).collect();
+
+        let estimated = run(string_inputs(&contents), |input| {
+            DistinctLineWorker::with_max_memory(input, 1024)
+        })
+        .unwrap();
+        assert!(estimated.approximate);
+        assert!(
+            estimated.count.abs_diff(20_000) < 20_000 * 3 / 100,
+            "estimated {} distinct lines",
+            estimated.count
+        );
+
+        let exact = run(string_inputs(&contents), |input| {
+            DistinctLineWorker::with_max_memory(input, 64 * 1024 * 1024)
+        })
+        .unwrap();
+        assert_eq!(
+            exact,
+            DistinctLines {
+                count: 20_000,
+                approximate: false
+            }
+        );
+    }
+
+    #[cfg(feature = "xxhash")]
+    #[test]
+    fn the_estimate_past_the_limit_uses_the_given_hasher() {
+        let contents: Vec<String> = (0..4)
+            .map(|part| {
+                (0..1_000)
+                    .map(|line| format!("part {part} line {line}\n"))
+                    .collect()
+            })
+            .collect();
+        let contents: Vec<&str> = contents.iter().map(String::as_str).collect();
+
+        // Merging sketches from different hashers fails a debug assertion.
+        let estimated = run(string_inputs(&contents), |input| {
+            DistinctLineWorker::with_hasher(input, 1024, ContentHasher::Xxh3)
+        })
+        .unwrap();
+        assert!(estimated.approximate);
+        assert!(
+            estimated.count.abs_diff(4_000) < 4_000 * 3 / 100,
+            "estimated {} distinct lines",
+            estimated.count
+        );
+    }
+}
@@ -0,0 +1,75 @@
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Combine, GenericInputData, MapReduceError, MapReducer, Mapper, Reducer, StringInputData,
+};
+
+// Counts the distinct lines that occur at least twice within one input; a line
+// repeated in two different inputs but once in each is not a duplicate.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct DuplicateLineWorker {
+    #[serde(skip, default = "super::no_input")]
+    input_data: Arc<dyn GenericInputData<String>>,
+    result: usize,
+}
+
+impl DuplicateLineWorker {
+    pub fn new(input_data: Arc<dyn GenericInputData<String>>) -> Self {
+        Self {
+            input_data,
+            result: 0,
+        }
+    }
+}
+
+impl Mapper for DuplicateLineWorker {
+    fn map(&mut self) -> Result<(), MapReduceError> {
+        let data = self.input_data.read()?;
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for line in data.lines() {
+            *counts.entry(line).or_insert(0) += 1;
+        }
+        self.result = counts.values().filter(|&&count| count >= 2).count();
+        Ok(())
+    }
+
+    fn input_path(&self) -> Option<&Path> {
+        self.input_data.path()
+    }
+}
+
+impl Reducer for DuplicateLineWorker {
+    type Output = usize;
+
+    fn reduce(&mut self, other: &Self) {
+        self.result += other.result;
+    }
+
+    fn get_result(&self) -> Self::Output {
+        self.result
+    }
+}
+
+impl Combine for DuplicateLineWorker {
+    fn identity() -> Self {
+        Self::new(Arc::new(StringInputData::default()))
+    }
+}
+
+impl MapReducer for DuplicateLineWorker {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{run, test_util::string_inputs};
+
+    #[test]
+    fn counts_lines_repeated_within_each_file() {
+        // "a" and "b" repeat in the first file; "a" in the second only repeats
+        // across files, which does not count.
+        let inputs = string_inputs(&["a\nb\na\na\nc\nb", "a\nx", "y\ny"]);
+        assert_eq!(run(inputs, DuplicateLineWorker::new).unwrap(), 3);
+    }
+}
@@ -0,0 +1,127 @@
+use std::{path::Path, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Combine, GenericInputData, MapReduceError, MapReducer, Mapper, Reducer, StringInputData,
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum HeadTailOrder {
+    /// The smallest first line and the largest last line across all inputs.
+    Lexicographic,
+    /// The first line of the first input and the last line of the last one.
+    Input,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HeadTailWorker {
+    #[serde(skip, default = "super::no_input")]
+    input_data: Arc<dyn GenericInputData<String>>,
+    order: HeadTailOrder,
+    head: Option<String>,
+    tail: Option<String>,
+}
+
+impl HeadTailWorker {
+    pub fn new(input_data: Arc<dyn GenericInputData<String>>, order: HeadTailOrder) -> Self {
+        Self {
+            input_data,
+            order,
+            head: None,
+            tail: None,
+        }
+    }
+}
+
+impl Mapper for HeadTailWorker {
+    fn map(&mut self) -> Result<(), MapReduceError> {
+        let data = self.input_data.read()?;
+        let mut lines = data.lines();
+        self.head = lines.next().map(str::to_owned);
+        self.tail = lines.next_back().map(str::to_owned).or(self.head.clone());
+        Ok(())
+    }
+
+    fn input_path(&self) -> Option<&Path> {
+        self.input_data.path()
+    }
+}
+
+impl Reducer for HeadTailWorker {
+    type Output = (String, String);
+
+    fn reduce(&mut self, other: &Self) {
+        // Inputs without lines, the identity included, have no order of their
+        // own to keep, so they take on the other's.
+        if self.head.is_none() {
+            self.order = other.order;
+            self.head = other.head.clone();
+            self.tail = other.tail.clone();
+            return;
+        }
+        let (Some(head), Some(tail)) = (&other.head, &other.tail) else {
+            return;
+        };
+        match self.order {
+            HeadTailOrder::Lexicographic => {
+                if self.head.as_ref().is_some_and(|current| head < current) {
+                    self.head = Some(head.clone());
+                }
+                if self.tail.as_ref().is_some_and(|current| tail > current) {
+                    self.tail = Some(tail.clone());
+                }
+            }
+            HeadTailOrder::Input => self.tail = Some(tail.clone()),
+        }
+    }
+
+    fn get_result(&self) -> Self::Output {
+        (
+            self.head.clone().unwrap_or_default(),
+            self.tail.clone().unwrap_or_default(),
+        )
+    }
+}
+
+impl Combine for HeadTailWorker {
+    fn identity() -> Self {
+        Self::new(
+            Arc::new(StringInputData::default()),
+            HeadTailOrder::Lexicographic,
+        )
+    }
+}
+
+impl MapReducer for HeadTailWorker {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{create_workers, generate_inputs, run_map_reduce, test_util::TempDir, RunOptions};
+
+    fn head_tail(dir: &TempDir, order: HeadTailOrder) -> (String, String) {
+        let workers = create_workers(generate_inputs(dir.path()).unwrap(), |input| {
+            HeadTailWorker::new(input, order)
+        });
+        let options = RunOptions::new().ordered(true);
+        run_map_reduce(workers, options).unwrap().result
+    }
+
+    #[test]
+    fn combines_the_head_and_tail_of_two_files() {
+        let dir = TempDir::new();
+        dir.write("a.txt", "mango\napple\nzebra\nkiwi");
+        dir.write("b.txt", "banana\nyak\ncherry");
+
+        let expected = |head: &str, tail: &str| (head.to_string(), tail.to_string());
+        assert_eq!(
+            head_tail(&dir, HeadTailOrder::Lexicographic),
+            expected("banana", "kiwi")
+        );
+        assert_eq!(
+            head_tail(&dir, HeadTailOrder::Input),
+            expected("mango", "cherry")
+        );
+    }
+}
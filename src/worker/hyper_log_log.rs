@@ -0,0 +1,177 @@
+use std::{path::Path, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Combine, ContentHasher, GenericInputData, MapReduceError, MapReducer, Mapper, Reducer,
+    StringInputData, WorkerOutput,
+};
+
+// 2^14 registers keep the standard error around 0.8% in 16 KiB per worker,
+// however many distinct lines there are.
+const PRECISION: u32 = 14;
+const REGISTERS: usize = 1 << PRECISION;
+
+#[derive(Clone, Serialize, Deserialize)]
+pub(crate) struct Sketch {
+    hasher: ContentHasher,
+    registers: Vec<u8>,
+}
+
+impl Sketch {
+    pub(crate) fn new(hasher: ContentHasher) -> Self {
+        Self {
+            hasher,
+            registers: vec![0; REGISTERS],
+        }
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.registers.fill(0);
+    }
+
+    pub(crate) fn insert(&mut self, line: &str) {
+        let hash = self.hasher.hash(line);
+
+        let index = (hash >> (64 - PRECISION)) as usize;
+        let rank = ((hash << PRECISION).leading_zeros() + 1).min(64 - PRECISION + 1) as u8;
+        self.registers[index] = self.registers[index].max(rank);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.registers.iter().all(|&register| register == 0)
+    }
+
+    /// An empty sketch takes on the other's hasher, so that an identity built
+    /// with the default one can be merged into. Anything else filled by a
+    /// different hasher would give a meaningless estimate.
+    pub(crate) fn merge(&mut self, other: &Self) {
+        if other.is_empty() {
+            return;
+        }
+        if self.is_empty() {
+            self.hasher = other.hasher;
+        }
+        debug_assert_eq!(
+            self.hasher, other.hasher,
+            "Sketches filled by different hashers cannot be merged"
+        );
+        for (register, &other) in self.registers.iter_mut().zip(&other.registers) {
+            *register = (*register).max(other);
+        }
+    }
+
+    pub(crate) fn estimate(&self) -> usize {
+        let m = self.registers.len() as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self
+            .registers
+            .iter()
+            .map(|&register| 2f64.powi(-i32::from(register)))
+            .sum();
+        let raw = alpha * m * m / sum;
+
+        // Linear counting is far more accurate while many registers are still empty.
+        let empty = self
+            .registers
+            .iter()
+            .filter(|&&register| register == 0)
+            .count();
+        let estimate = if raw <= 2.5 * m && empty > 0 {
+            m * (m / empty as f64).ln()
+        } else {
+            raw
+        };
+        estimate.round() as usize
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct HyperLogLogWorker {
+    #[serde(skip, default = "super::no_input")]
+    input_data: Arc<dyn GenericInputData<String>>,
+    sketch: Sketch,
+}
+
+impl HyperLogLogWorker {
+    pub fn new(input_data: Arc<dyn GenericInputData<String>>) -> Self {
+        Self::with_hasher(input_data, ContentHasher::default())
+    }
+
+    /// Registers filled by different hashers cannot be merged, so every worker
+    /// in a run has to use the same one.
+    pub fn with_hasher(
+        input_data: Arc<dyn GenericInputData<String>>,
+        hasher: ContentHasher,
+    ) -> Self {
+        Self {
+            input_data,
+            sketch: Sketch::new(hasher),
+        }
+    }
+}
+
+impl Mapper for HyperLogLogWorker {
+    fn map(&mut self) -> Result<(), MapReduceError> {
+        let data = self.input_data.read()?;
+        self.sketch.clear();
+        for line in data.lines() {
+            self.sketch.insert(line);
+        }
+        Ok(())
+    }
+
+    fn input_path(&self) -> Option<&Path> {
+        self.input_data.path()
+    }
+}
+
+impl Reducer for HyperLogLogWorker {
+    type Output = usize;
+
+    fn reduce(&mut self, other: &Self) {
+        self.sketch.merge(&other.sketch);
+    }
+
+    fn get_result(&self) -> Self::Output {
+        self.sketch.estimate()
+    }
+
+    fn output(&self) -> WorkerOutput {
+        WorkerOutput::Distinct {
+            count: self.get_result(),
+            approximate: true,
+        }
+    }
+}
+
+impl Combine for HyperLogLogWorker {
+    fn identity() -> Self {
+        Self::new(Arc::new(StringInputData::default()))
+    }
+}
+
+impl MapReducer for HyperLogLogWorker {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run;
+
+    #[test]
+    fn estimates_100k_lines_within_a_few_percent() {
+        // 100k lines over four inputs, each line appearing twice.
+        let inputs: Vec<Box<dyn GenericInputData<String>>> = (0..4)
+            .map(|part| {
+                let content: String = (0..25_000)
+                    .map(|index| format!("line {}\n", (part * 25_000 + index) % 50_000))
+                    .collect();
+                Box::new(StringInputData::new(content)) as Box<dyn GenericInputData<String>>
+            })
+            .collect();
+
+        let estimate = run(inputs, HyperLogLogWorker::new).unwrap() as f64;
+        let error = (estimate - 50_000.0).abs() / 50_000.0;
+        assert!(error < 0.03, "estimated {estimate} distinct lines");
+    }
+}
@@ -0,0 +1,98 @@
+use std::{path::Path, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Combine, GenericInputData, MapReduceError, MapReducer, Mapper, Reducer, StringInputData,
+    WorkerOutput,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JsonRecords {
+    pub records: usize,
+    /// Non-blank lines that are not valid JSON.
+    pub malformed: usize,
+}
+
+impl From<JsonRecords> for WorkerOutput {
+    fn from(result: JsonRecords) -> Self {
+        Self::Count(result.records)
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct JsonRecordCountWorker {
+    #[serde(skip, default = "super::no_input")]
+    input_data: Arc<dyn GenericInputData<String>>,
+    result: JsonRecords,
+}
+
+impl JsonRecordCountWorker {
+    pub fn new(input_data: Arc<dyn GenericInputData<String>>) -> Self {
+        Self {
+            input_data,
+            result: JsonRecords::default(),
+        }
+    }
+}
+
+impl Mapper for JsonRecordCountWorker {
+    fn map(&mut self) -> Result<(), MapReduceError> {
+        let data = self.input_data.read()?;
+        self.result = JsonRecords::default();
+        for line in data.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            match serde_json::from_str::<serde_json::Value>(line) {
+                Ok(_) => self.result.records += 1,
+                Err(_) => self.result.malformed += 1,
+            }
+        }
+        Ok(())
+    }
+
+    fn input_path(&self) -> Option<&Path> {
+        self.input_data.path()
+    }
+}
+
+impl Reducer for JsonRecordCountWorker {
+    type Output = JsonRecords;
+
+    fn reduce(&mut self, other: &Self) {
+        self.result.records += other.result.records;
+        self.result.malformed += other.result.malformed;
+    }
+
+    fn get_result(&self) -> Self::Output {
+        self.result
+    }
+}
+
+impl Combine for JsonRecordCountWorker {
+    fn identity() -> Self {
+        Self::new(Arc::new(StringInputData::default()))
+    }
+}
+
+impl MapReducer for JsonRecordCountWorker {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{run, test_util::string_inputs};
+
+    #[test]
+    fn counts_valid_records_and_malformed_lines() {
+        let inputs = string_inputs(&[
+            "{\"id\": 1}\n{\"id\": 2\n\n[1, 2]\n",
+            "not json\n{\"nested\": {\"ok\": true}}\n   \n",
+        ]);
+        let records = run(inputs, JsonRecordCountWorker::new).unwrap();
+        assert_eq!(
+            records,
+            JsonRecords {
+                records: 3,
+                malformed: 2
+            }
+        );
+    }
+}
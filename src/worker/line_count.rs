@@ -0,0 +1,113 @@
+use std::{path::Path, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Combine, GenericInputData, MapReduceError, MapReducer, Mapper, Reducer, StringInputData,
+};
+
+#[derive(Serialize, Deserialize)]
+pub struct LineCountWorker {
+    #[serde(skip, default = "super::no_input")]
+    input_data: Arc<dyn GenericInputData<String>>,
+    delimiter: u8,
+    result: usize,
+}
+
+impl LineCountWorker {
+    pub fn new(input_data: Arc<dyn GenericInputData<String>>) -> Self {
+        Self::with_delimiter(input_data, b'\n')
+    }
+
+    pub fn with_delimiter(input_data: Arc<dyn GenericInputData<String>>, delimiter: u8) -> Self {
+        Self {
+            input_data,
+            delimiter,
+            result: 0,
+        }
+    }
+}
+
+impl Mapper for LineCountWorker {
+    fn map(&mut self) -> Result<(), MapReduceError> {
+        self.result = if self.delimiter == b'\n' {
+            self.input_data.count_lines()?
+        } else {
+            count_records(self.input_data.read()?.as_bytes(), self.delimiter)
+        };
+        Ok(())
+    }
+
+    fn input_path(&self) -> Option<&Path> {
+        self.input_data.path()
+    }
+}
+
+impl Reducer for LineCountWorker {
+    type Output = usize;
+
+    fn reduce(&mut self, other: &Self) {
+        self.result += other.result;
+    }
+
+    fn get_result(&self) -> Self::Output {
+        self.result
+    }
+}
+
+impl Combine for LineCountWorker {
+    fn identity() -> Self {
+        Self::new(Arc::new(StringInputData::default()))
+    }
+}
+
+impl MapReducer for LineCountWorker {}
+
+// A trailing delimiter terminates the last record rather than starting a new,
+// empty one, matching how `str::lines` treats a final newline.
+fn count_records(data: &[u8], delimiter: u8) -> usize {
+    let delimiters = data.iter().filter(|&&byte| byte == delimiter).count();
+    let unterminated = data.last().is_some_and(|&byte| byte != delimiter);
+    delimiters + usize::from(unterminated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{aggregate, create_workers, test_util::string_inputs};
+
+    fn count(content: &str, delimiter: u8) -> usize {
+        let mut worker =
+            LineCountWorker::with_delimiter(Arc::new(StringInputData::new(content)), delimiter);
+        worker.map().unwrap();
+        worker.get_result()
+    }
+
+    #[test]
+    fn counts_null_delimited_records() {
+        assert_eq!(count("./a.txt\0./b c.txt\0./d.txt\0", b'\0'), 3);
+        assert_eq!(count("./a.txt\0./b.txt", b'\0'), 2);
+        assert_eq!(count("", b'\0'), 0);
+    }
+
+    #[test]
+    fn newlines_stay_the_default() {
+        assert_eq!(count("one\r\ntwo\nthree", b'\n'), 3);
+        let mut worker = LineCountWorker::new(Arc::new(StringInputData::new("a\0b\n")));
+        worker.map().unwrap();
+        assert_eq!(worker.get_result(), 1);
+    }
+
+    #[test]
+    fn handles_share_one_worker_instead_of_copying_it() {
+        let workers = create_workers(string_inputs(&["a\nb", "c"]), LineCountWorker::new);
+        let handles = workers.clone();
+        assert_eq!(aggregate(workers).unwrap(), 3);
+
+        let results: Vec<usize> = handles
+            .iter()
+            .map(|worker| worker.lock().get_result())
+            .collect();
+        assert_eq!(results, [2, 1]);
+    }
+}
@@ -0,0 +1,111 @@
+use std::{path::Path, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Combine, GenericInputData, MapReduceError, MapReducer, Mapper, Reducer, StringInputData,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Percentiles {
+    pub p50: usize,
+    pub p90: usize,
+    pub p99: usize,
+}
+
+// Every line length is kept so the percentiles are exact, which costs one
+// `usize` per line across the whole run.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LineLengthPercentileWorker {
+    #[serde(skip, default = "super::no_input")]
+    input_data: Arc<dyn GenericInputData<String>>,
+    lengths: Vec<usize>,
+}
+
+impl LineLengthPercentileWorker {
+    pub fn new(input_data: Arc<dyn GenericInputData<String>>) -> Self {
+        Self {
+            input_data,
+            lengths: Vec::new(),
+        }
+    }
+}
+
+impl Mapper for LineLengthPercentileWorker {
+    fn map(&mut self) -> Result<(), MapReduceError> {
+        let data = self.input_data.read()?;
+        self.lengths = data.lines().map(|line| line.chars().count()).collect();
+        Ok(())
+    }
+
+    fn input_path(&self) -> Option<&Path> {
+        self.input_data.path()
+    }
+}
+
+impl Reducer for LineLengthPercentileWorker {
+    type Output = Percentiles;
+
+    fn reduce(&mut self, other: &Self) {
+        self.lengths.extend_from_slice(&other.lengths);
+    }
+
+    fn get_result(&self) -> Self::Output {
+        let mut lengths = self.lengths.clone();
+        lengths.sort_unstable();
+        Percentiles {
+            p50: nearest_rank(&lengths, 50),
+            p90: nearest_rank(&lengths, 90),
+            p99: nearest_rank(&lengths, 99),
+        }
+    }
+}
+
+impl Combine for LineLengthPercentileWorker {
+    fn identity() -> Self {
+        Self::new(Arc::new(StringInputData::default()))
+    }
+}
+
+impl MapReducer for LineLengthPercentileWorker {}
+
+fn nearest_rank(sorted: &[usize], percentile: usize) -> usize {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (percentile * sorted.len()).div_ceil(100);
+    sorted[rank.max(1) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run;
+
+    fn lines_of_lengths(lengths: impl Iterator<Item = usize>) -> Box<dyn GenericInputData<String>> {
+        let content: String = lengths.map(|length| "x".repeat(length) + "\n").collect();
+        Box::new(StringInputData::new(content))
+    }
+
+    #[test]
+    fn percentiles_of_lengths_one_to_a_hundred() {
+        // Split across inputs and out of order, so the lengths must be merged and sorted.
+        let inputs = vec![
+            lines_of_lengths((1..=100).rev().step_by(2)),
+            lines_of_lengths((1..=99).step_by(2)),
+        ];
+        let percentiles = run(inputs, LineLengthPercentileWorker::new).unwrap();
+        assert_eq!(
+            percentiles,
+            Percentiles {
+                p50: 50,
+                p90: 90,
+                p99: 99
+            }
+        );
+        assert_eq!(
+            LineLengthPercentileWorker::identity().get_result(),
+            Percentiles::default()
+        );
+    }
+}
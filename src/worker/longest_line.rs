@@ -0,0 +1,82 @@
+use std::{path::Path, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Combine, GenericInputData, MapReduceError, MapReducer, Mapper, Reducer, StringInputData,
+    WorkerOutput,
+};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LongestLineWorker {
+    #[serde(skip, default = "super::no_input")]
+    input_data: Arc<dyn GenericInputData<String>>,
+    result: usize,
+}
+
+impl LongestLineWorker {
+    pub fn new(input_data: Arc<dyn GenericInputData<String>>) -> Self {
+        Self {
+            input_data,
+            result: 0,
+        }
+    }
+}
+
+impl Mapper for LongestLineWorker {
+    fn map(&mut self) -> Result<(), MapReduceError> {
+        let data = self.input_data.read()?;
+        self.result = data
+            .lines()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        Ok(())
+    }
+
+    fn input_path(&self) -> Option<&Path> {
+        self.input_data.path()
+    }
+}
+
+impl Reducer for LongestLineWorker {
+    type Output = usize;
+
+    fn reduce(&mut self, other: &Self) {
+        self.result = self.result.max(other.get_result());
+    }
+
+    fn get_result(&self) -> Self::Output {
+        self.result
+    }
+
+    fn output(&self) -> WorkerOutput {
+        WorkerOutput::Max(self.result)
+    }
+}
+
+impl Combine for LongestLineWorker {
+    fn identity() -> Self {
+        Self::new(Arc::new(StringInputData::default()))
+    }
+}
+
+impl MapReducer for LongestLineWorker {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{create_workers, generate_inputs, run_map_reduce, test_util::TempDir, RunOptions};
+
+    #[test]
+    fn reduces_to_the_longest_line_rather_than_the_sum() {
+        let dir = TempDir::new();
+        dir.write("a.txt", "short\nlines\n");
+        dir.write("b.txt", "tiny\nthe longest line\nok\n");
+
+        let workers = create_workers(generate_inputs(dir.path()).unwrap(), LongestLineWorker::new);
+        let summary = run_map_reduce(workers, RunOptions::new()).unwrap();
+        assert_eq!(summary.result, 16);
+        assert_eq!(LongestLineWorker::identity().output(), WorkerOutput::Max(0));
+    }
+}
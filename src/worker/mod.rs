@@ -0,0 +1,261 @@
+mod average_line_length;
+mod byte_histogram;
+mod byte_size;
+mod char_count;
+mod char_frequency;
+mod composite;
+mod distinct_line;
+mod duplicate_line;
+mod head_tail;
+mod hyper_log_log;
+mod json_record_count;
+mod line_count;
+mod line_length_percentile;
+mod longest_line;
+mod multi_metric;
+mod multi_pattern_count;
+mod non_blank_line_count;
+mod normalize;
+mod predicate_line;
+mod regex_match_count;
+mod sum_of_numbers;
+mod top_k_word;
+mod weighted;
+mod word_count;
+mod word_frequency;
+
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+use parking_lot::Mutex;
+
+use crate::{
+    input::PrefetchedInputData, ContentHasher, GenericInputData, MapReduceError, StringInputData,
+    WorkerOutput,
+};
+
+pub use average_line_length::AverageLineLengthWorker;
+pub use byte_histogram::ByteHistogramWorker;
+pub use byte_size::ByteSizeWorker;
+pub use char_count::{CharCountWorker, CountMode};
+pub use char_frequency::CharFrequencyWorker;
+pub use composite::CompositeWorker;
+pub use distinct_line::{DistinctLineWorker, DistinctLines};
+pub use duplicate_line::DuplicateLineWorker;
+pub use head_tail::{HeadTailOrder, HeadTailWorker};
+pub use hyper_log_log::HyperLogLogWorker;
+pub use json_record_count::{JsonRecordCountWorker, JsonRecords};
+pub use line_count::LineCountWorker;
+pub use line_length_percentile::{LineLengthPercentileWorker, Percentiles};
+pub use longest_line::LongestLineWorker;
+pub use multi_metric::{Metrics, MultiMetricWorker};
+pub use multi_pattern_count::MultiPatternCountWorker;
+pub use non_blank_line_count::NonBlankLineCountWorker;
+pub use normalize::TextNormalizer;
+pub use predicate_line::{LinePredicate, PredicateLineWorker};
+pub use regex_match_count::{RegexMatchCountWorker, RegexOptions};
+pub use sum_of_numbers::{NumberSum, SumOfNumbersWorker};
+pub use top_k_word::TopKWordWorker;
+pub use weighted::WeightedWorker;
+pub use word_count::WordCountWorker;
+pub use word_frequency::{top_words, WordCounts, WordFrequencyWorker};
+
+pub trait Mapper: Send + Sync {
+    fn map(&mut self) -> Result<(), MapReduceError>;
+
+    fn input_path(&self) -> Option<&Path> {
+        None
+    }
+}
+
+pub trait Reducer: Send + Sync {
+    // Results cross threads, and a cancelled run hands its partial result back
+    // inside the non-generic error type.
+    type Output: Send + Sync + 'static;
+
+    fn reduce(&mut self, other: &Self);
+    fn get_result(&self) -> Self::Output;
+
+    fn output(&self) -> WorkerOutput
+    where
+        Self::Output: Into<WorkerOutput>,
+    {
+        self.get_result().into()
+    }
+}
+
+pub trait Combine: Reducer + Sized {
+    fn identity() -> Self;
+
+    fn combine(mut self, other: Self) -> Self {
+        self.reduce(&other);
+        self
+    }
+}
+
+/// Workers are shared between the caller and the thread pool as
+/// `Arc<Mutex<W>>`, so every worker must be both `Send` and `Sync`.
+pub trait MapReducer: Mapper + Reducer + Combine + Send + Sync {}
+
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync + ?Sized>() {}
+
+    assert_send_sync::<crate::FileInputData>();
+    assert_send_sync::<dyn GenericInputData<String>>();
+    assert_send_sync::<Arc<Mutex<LineCountWorker>>>();
+    assert_send_sync::<Arc<Mutex<WordCountWorker>>>();
+    assert_send_sync::<Arc<Mutex<WordFrequencyWorker>>>();
+};
+
+// Workers are serialized without their input, which has already been read by
+// the time their mapped state is worth sending anywhere.
+fn no_input() -> Arc<dyn GenericInputData<String>> {
+    Arc::new(StringInputData::default())
+}
+
+pub fn create_workers<T, W, F>(
+    input_list: Vec<Box<dyn GenericInputData<T>>>,
+    make_worker: F,
+) -> Vec<Arc<Mutex<W>>>
+where
+    W: MapReducer,
+    F: Fn(Arc<dyn GenericInputData<T>>) -> W,
+{
+    let mut workers = Vec::new();
+
+    for input_data in input_list {
+        workers.push(Arc::new(Mutex::new(make_worker(input_data.into()))));
+    }
+
+    workers
+}
+
+/// Like [`create_workers`], but inputs with identical content share a single
+/// worker. The shared worker is mapped once and reduced once per duplicate.
+///
+/// Every input is read up front to compare it, and the content of each distinct
+/// one is kept until its worker maps it, so nothing is read twice. An input
+/// that cannot be read gets a worker of its own, which fails when mapped.
+pub fn create_workers_deduped<W, F>(
+    input_list: Vec<Box<dyn GenericInputData<String>>>,
+    make_worker: F,
+) -> Vec<Arc<Mutex<W>>>
+where
+    W: MapReducer,
+    F: Fn(Arc<dyn GenericInputData<String>>) -> W,
+{
+    create_workers_deduped_with(input_list, make_worker, ContentHasher::default())
+}
+
+/// Like [`create_workers_deduped`], but content is compared by `hasher`.
+pub fn create_workers_deduped_with<W, F>(
+    input_list: Vec<Box<dyn GenericInputData<String>>>,
+    make_worker: F,
+    hasher: ContentHasher,
+) -> Vec<Arc<Mutex<W>>>
+where
+    W: MapReducer,
+    F: Fn(Arc<dyn GenericInputData<String>>) -> W,
+{
+    let mut by_hash: HashMap<u64, Arc<Mutex<W>>> = HashMap::new();
+    let mut workers = Vec::new();
+
+    for input_data in input_list {
+        let input_data: Arc<dyn GenericInputData<String>> = input_data.into();
+        let content = match input_data.read() {
+            Ok(content) => content,
+            Err(error) => {
+                log::warn!("could not read input to deduplicate it: {error}");
+                workers.push(Arc::new(Mutex::new(make_worker(input_data))));
+                continue;
+            }
+        };
+        let worker = by_hash.entry(hasher.hash(&content)).or_insert_with(|| {
+            let prefetched = PrefetchedInputData::new(input_data, content);
+            Arc::new(Mutex::new(make_worker(Arc::new(prefetched))))
+        });
+        workers.push(Arc::clone(worker));
+    }
+
+    workers
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, io::Cursor, sync::atomic::Ordering};
+
+    use super::*;
+    use crate::{
+        generate_inputs, run, run_map_reduce,
+        test_util::{CountingInput, TempDir},
+        Mapper, ReaderInputData, RunOptions, StringInputData,
+    };
+
+    #[test]
+    fn an_empty_directory_reduces_to_the_identity() {
+        let dir = TempDir::new();
+        let input_list = generate_inputs(dir.path()).unwrap();
+        assert_eq!(run(input_list, LineCountWorker::new).unwrap(), 0);
+        assert_eq!(LineCountWorker::identity().get_result(), 0);
+    }
+
+    #[test]
+    fn combine_defaults_to_reduce() {
+        let mut left = LineCountWorker::new(Arc::new(StringInputData::new("a\nb")));
+        let mut right = LineCountWorker::new(Arc::new(StringInputData::new("c")));
+        left.map().unwrap();
+        right.map().unwrap();
+        let combined = LineCountWorker::identity().combine(left).combine(right);
+        assert_eq!(combined.get_result(), 3);
+    }
+
+    #[test]
+    fn deduplicated_runs_match_naive_runs() {
+        let dir = TempDir::new();
+        dir.write("a.txt", "one\ntwo\nthree");
+        dir.write("copy-of-a.txt", "one\ntwo\nthree");
+        dir.write("b.txt", "four");
+
+        let naive = create_workers(generate_inputs(dir.path()).unwrap(), LineCountWorker::new);
+        let deduped =
+            create_workers_deduped(generate_inputs(dir.path()).unwrap(), LineCountWorker::new);
+        assert_eq!(deduped.len(), 3);
+        assert!(Arc::ptr_eq(&deduped[0], &deduped[2]));
+
+        let naive = run_map_reduce(naive, RunOptions::new()).unwrap();
+        let deduped = run_map_reduce(deduped, RunOptions::new()).unwrap();
+        assert_eq!(deduped.result, naive.result);
+        assert_eq!(deduped.result, 7);
+    }
+
+    #[test]
+    fn deduplicated_inputs_are_read_once() {
+        let (first, first_reads) = CountingInput::new("same\ncontent");
+        let (second, second_reads) = CountingInput::new("same\ncontent");
+        let input_list: Vec<Box<dyn GenericInputData<String>>> = vec![
+            Box::new(first),
+            Box::new(second),
+            // A one-shot source cannot be read a second time when mapped.
+            Box::new(ReaderInputData::new(Cursor::new("piped\n"))),
+        ];
+
+        let workers = create_workers_deduped(input_list, LineCountWorker::new);
+        let summary = run_map_reduce(workers, RunOptions::new()).unwrap();
+        assert_eq!((summary.result, summary.inputs_failed), (5, 0));
+        assert_eq!(first_reads.load(Ordering::SeqCst), 1);
+        assert_eq!(second_reads.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn unreadable_inputs_are_not_deduplicated() {
+        let dir = TempDir::new();
+        dir.write("a.txt", "one");
+        let gone = dir.write("b.txt", "two");
+        let input_list = generate_inputs(dir.path()).unwrap();
+        fs::remove_file(&gone).unwrap();
+
+        let workers = create_workers_deduped(input_list, LineCountWorker::new);
+        let summary = run_map_reduce(workers, RunOptions::new()).unwrap();
+        assert_eq!((summary.result, summary.inputs_failed), (1, 1));
+        assert_eq!(summary.failures[0].0, gone);
+    }
+}
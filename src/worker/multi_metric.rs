@@ -0,0 +1,104 @@
+use std::{ops::AddAssign, path::Path, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Combine, GenericInputData, MapReduceError, MapReducer, Mapper, Reducer, StringInputData,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Metrics {
+    pub lines: usize,
+    pub words: usize,
+    pub bytes: usize,
+}
+
+impl AddAssign for Metrics {
+    fn add_assign(&mut self, other: Self) {
+        self.lines += other.lines;
+        self.words += other.words;
+        self.bytes += other.bytes;
+    }
+}
+
+// Computes what `LineCountWorker`, `WordCountWorker` and `ByteSizeWorker`
+// would, from a single read of each input.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct MultiMetricWorker {
+    #[serde(skip, default = "super::no_input")]
+    input_data: Arc<dyn GenericInputData<String>>,
+    result: Metrics,
+}
+
+impl MultiMetricWorker {
+    pub fn new(input_data: Arc<dyn GenericInputData<String>>) -> Self {
+        Self {
+            input_data,
+            result: Metrics::default(),
+        }
+    }
+}
+
+impl Mapper for MultiMetricWorker {
+    fn map(&mut self) -> Result<(), MapReduceError> {
+        let data = self.input_data.read()?;
+        self.result = Metrics {
+            lines: data.lines().count(),
+            words: data.split_whitespace().count(),
+            bytes: data.len(),
+        };
+        Ok(())
+    }
+
+    fn input_path(&self) -> Option<&Path> {
+        self.input_data.path()
+    }
+}
+
+impl Reducer for MultiMetricWorker {
+    type Output = Metrics;
+
+    fn reduce(&mut self, other: &Self) {
+        self.result += other.result;
+    }
+
+    fn get_result(&self) -> Self::Output {
+        self.result
+    }
+}
+
+impl Combine for MultiMetricWorker {
+    fn identity() -> Self {
+        Self::new(Arc::new(StringInputData::default()))
+    }
+}
+
+impl MapReducer for MultiMetricWorker {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        generate_inputs, run, test_util::TempDir, ByteSizeWorker, LineCountWorker, WordCountWorker,
+    };
+
+    #[test]
+    fn metrics_match_the_individual_workers() {
+        let dir = TempDir::new();
+        dir.write("a.txt", "the quick brown\nfox\n");
+        dir.write("b.txt", "héllo  wörld\r\n\nlast line");
+        dir.write("c.txt", "");
+
+        let inputs = || generate_inputs(dir.path()).unwrap();
+        let metrics = run(inputs(), MultiMetricWorker::new).unwrap();
+        assert_eq!(
+            metrics,
+            Metrics {
+                lines: run(inputs(), LineCountWorker::new).unwrap(),
+                words: run(inputs(), WordCountWorker::new).unwrap(),
+                bytes: run(inputs(), ByteSizeWorker::new).unwrap(),
+            }
+        );
+        assert_eq!(metrics.lines, 5);
+    }
+}
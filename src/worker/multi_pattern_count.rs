@@ -0,0 +1,107 @@
+use std::{collections::HashMap, path::Path, sync::Arc};
+
+use regex::Regex;
+
+use crate::{
+    Combine, GenericInputData, MapReduceError, MapReducer, Mapper, Reducer, StringInputData,
+};
+
+#[derive(Clone)]
+pub struct MultiPatternCountWorker {
+    input_data: Arc<dyn GenericInputData<String>>,
+    patterns: Vec<(String, Regex)>,
+    result: HashMap<String, usize>,
+}
+
+impl MultiPatternCountWorker {
+    pub fn new(
+        input_data: Arc<dyn GenericInputData<String>>,
+        patterns: Vec<(String, Regex)>,
+    ) -> Self {
+        Self {
+            input_data,
+            patterns,
+            result: HashMap::new(),
+        }
+    }
+}
+
+impl Mapper for MultiPatternCountWorker {
+    fn map(&mut self) -> Result<(), MapReduceError> {
+        let data = self.input_data.read()?;
+        let mut counts = vec![0; self.patterns.len()];
+        for line in data.lines() {
+            for (count, (_, pattern)) in counts.iter_mut().zip(&self.patterns) {
+                if pattern.is_match(line) {
+                    *count += 1;
+                }
+            }
+        }
+        // Every label is present, even with no matches, so results line up
+        // across inputs.
+        self.result = self
+            .patterns
+            .iter()
+            .map(|(label, _)| label.clone())
+            .zip(counts)
+            .collect();
+        Ok(())
+    }
+
+    fn input_path(&self) -> Option<&Path> {
+        self.input_data.path()
+    }
+}
+
+impl Reducer for MultiPatternCountWorker {
+    type Output = HashMap<String, usize>;
+
+    fn reduce(&mut self, other: &Self) {
+        for (label, &count) in &other.result {
+            *self.result.entry(label.clone()).or_insert(0) += count;
+        }
+    }
+
+    fn get_result(&self) -> Self::Output {
+        self.result.clone()
+    }
+}
+
+impl Combine for MultiPatternCountWorker {
+    fn identity() -> Self {
+        Self::new(Arc::new(StringInputData::default()), Vec::new())
+    }
+}
+
+impl MapReducer for MultiPatternCountWorker {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{run, test_util::string_inputs};
+
+    #[test]
+    fn counts_three_patterns_in_one_run() {
+        let patterns: Vec<(String, Regex)> = ["ERROR", "WARN", "INFO"]
+            .into_iter()
+            .map(|level| (level.to_string(), Regex::new(&format!("^{level}")).unwrap()))
+            .collect();
+        let inputs = string_inputs(&[
+            "ERROR disk\nINFO ok\nWARN cpu\nINFO ok",
+            "INFO start\nERROR net\nINFO stop",
+        ]);
+
+        let counts = run(inputs, |input| {
+            MultiPatternCountWorker::new(input, patterns.clone())
+        })
+        .unwrap();
+        assert_eq!(
+            counts,
+            HashMap::from([
+                ("ERROR".to_string(), 2),
+                ("WARN".to_string(), 1),
+                ("INFO".to_string(), 4)
+            ])
+        );
+    }
+}
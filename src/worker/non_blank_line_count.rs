@@ -0,0 +1,71 @@
+use std::{path::Path, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Combine, GenericInputData, MapReduceError, MapReducer, Mapper, Reducer, StringInputData,
+};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct NonBlankLineCountWorker {
+    #[serde(skip, default = "super::no_input")]
+    input_data: Arc<dyn GenericInputData<String>>,
+    result: usize,
+}
+
+impl NonBlankLineCountWorker {
+    pub fn new(input_data: Arc<dyn GenericInputData<String>>) -> Self {
+        Self {
+            input_data,
+            result: 0,
+        }
+    }
+}
+
+impl Mapper for NonBlankLineCountWorker {
+    fn map(&mut self) -> Result<(), MapReduceError> {
+        let data = self.input_data.read()?;
+        self.result = data.lines().filter(|line| !line.trim().is_empty()).count();
+        Ok(())
+    }
+
+    fn input_path(&self) -> Option<&Path> {
+        self.input_data.path()
+    }
+}
+
+impl Reducer for NonBlankLineCountWorker {
+    type Output = usize;
+
+    fn reduce(&mut self, other: &Self) {
+        self.result += other.result;
+    }
+
+    fn get_result(&self) -> Self::Output {
+        self.result
+    }
+}
+
+impl Combine for NonBlankLineCountWorker {
+    fn identity() -> Self {
+        Self::new(Arc::new(StringInputData::default()))
+    }
+}
+
+impl MapReducer for NonBlankLineCountWorker {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{create_workers, run_map_reduce, test_util::string_inputs, RunOptions};
+
+    #[test]
+    fn blank_and_whitespace_lines_are_not_counted() {
+        let inputs = string_inputs(&["one\n\ntwo\n   \nthree\n", "\t\nfour\n\n"]);
+        let workers = create_workers(inputs, NonBlankLineCountWorker::new);
+        assert_eq!(
+            run_map_reduce(workers, RunOptions::new()).unwrap().result,
+            4
+        );
+    }
+}
@@ -0,0 +1,48 @@
+use std::ops::{BitOr, BitOrAssign};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct TextNormalizer(u8);
+
+impl TextNormalizer {
+    pub const NONE: Self = Self(0);
+    pub const LOWERCASE: Self = Self(1);
+    pub const STRIP_PUNCTUATION: Self = Self(1 << 1);
+
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// Returns `None` when nothing is left of the token, e.g. a lone `--`.
+    pub fn apply(self, token: &str) -> Option<String> {
+        let token = if self.contains(Self::STRIP_PUNCTUATION) {
+            token.trim_matches(|c: char| c.is_ascii_punctuation())
+        } else {
+            token
+        };
+        if token.is_empty() {
+            return None;
+        }
+
+        if self.contains(Self::LOWERCASE) {
+            Some(token.to_lowercase())
+        } else {
+            Some(token.to_string())
+        }
+    }
+}
+
+impl BitOr for TextNormalizer {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+}
+
+impl BitOrAssign for TextNormalizer {
+    fn bitor_assign(&mut self, other: Self) {
+        self.0 |= other.0;
+    }
+}
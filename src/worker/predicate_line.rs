@@ -0,0 +1,133 @@
+use std::{path::Path, sync::Arc};
+
+use regex::Regex;
+
+use crate::{
+    Combine, GenericInputData, MapReduceError, MapReducer, Mapper, Reducer, StringInputData,
+};
+
+#[derive(Clone, Debug)]
+pub enum LinePredicate {
+    /// True when every predicate is; an empty list is always true.
+    And(Vec<LinePredicate>),
+    /// True when any predicate is; an empty list is never true.
+    Or(Vec<LinePredicate>),
+    Not(Box<LinePredicate>),
+    Contains(String),
+    Matches(Regex),
+}
+
+impl LinePredicate {
+    pub fn is_match(&self, line: &str) -> bool {
+        match self {
+            Self::And(predicates) => predicates.iter().all(|predicate| predicate.is_match(line)),
+            Self::Or(predicates) => predicates.iter().any(|predicate| predicate.is_match(line)),
+            Self::Not(predicate) => !predicate.is_match(line),
+            Self::Contains(needle) => line.contains(needle.as_str()),
+            Self::Matches(pattern) => pattern.is_match(line),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct PredicateLineWorker {
+    input_data: Arc<dyn GenericInputData<String>>,
+    predicate: Arc<LinePredicate>,
+    result: usize,
+}
+
+impl PredicateLineWorker {
+    pub fn new(
+        input_data: Arc<dyn GenericInputData<String>>,
+        predicate: impl Into<Arc<LinePredicate>>,
+    ) -> Self {
+        Self {
+            input_data,
+            predicate: predicate.into(),
+            result: 0,
+        }
+    }
+}
+
+impl Mapper for PredicateLineWorker {
+    fn map(&mut self) -> Result<(), MapReduceError> {
+        let data = self.input_data.read()?;
+        self.result = data
+            .lines()
+            .filter(|line| self.predicate.is_match(line))
+            .count();
+        Ok(())
+    }
+
+    fn input_path(&self) -> Option<&Path> {
+        self.input_data.path()
+    }
+}
+
+impl Reducer for PredicateLineWorker {
+    type Output = usize;
+
+    fn reduce(&mut self, other: &Self) {
+        self.result += other.result;
+    }
+
+    fn get_result(&self) -> Self::Output {
+        self.result
+    }
+}
+
+impl Combine for PredicateLineWorker {
+    fn identity() -> Self {
+        Self::new(
+            Arc::new(StringInputData::default()),
+            LinePredicate::Or(Vec::new()),
+        )
+    }
+}
+
+impl MapReducer for PredicateLineWorker {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{create_workers, generate_inputs, run_map_reduce, test_util::TempDir, RunOptions};
+
+    // ("ERROR" AND NOT "ignored") OR /^FATAL/
+    fn serious_errors() -> LinePredicate {
+        LinePredicate::Or(vec![
+            LinePredicate::And(vec![
+                LinePredicate::Contains("ERROR".to_string()),
+                LinePredicate::Not(Box::new(LinePredicate::Contains("ignored".to_string()))),
+            ]),
+            LinePredicate::Matches(Regex::new("^FATAL").unwrap()),
+        ])
+    }
+
+    #[test]
+    fn counts_lines_matching_a_combined_predicate() {
+        let dir = TempDir::new();
+        dir.write(
+            "a.log",
+            "ERROR disk full\nERROR ignored retry\nINFO ok\nFATAL out of memory\n",
+        );
+        dir.write(
+            "b.log",
+            "FATAL ERROR ignored but fatal\nWARN not FATAL\nERROR again\n",
+        );
+
+        let predicate = Arc::new(serious_errors());
+        let workers = create_workers(generate_inputs(dir.path()).unwrap(), |input| {
+            PredicateLineWorker::new(input, Arc::clone(&predicate))
+        });
+        assert_eq!(
+            run_map_reduce(workers, RunOptions::new()).unwrap().result,
+            4
+        );
+    }
+
+    #[test]
+    fn empty_combinations_have_fixed_answers() {
+        assert!(LinePredicate::And(Vec::new()).is_match("anything"));
+        assert!(!LinePredicate::Or(Vec::new()).is_match("anything"));
+    }
+}
@@ -0,0 +1,138 @@
+use std::{path::Path, sync::Arc};
+
+use regex::{Regex, RegexBuilder};
+
+use crate::{
+    Combine, GenericInputData, MapReduceError, MapReducer, Mapper, Reducer, StringInputData,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RegexOptions {
+    pub case_insensitive: bool,
+    pub multi_line: bool,
+}
+
+impl RegexOptions {
+    pub fn build(self, pattern: &str) -> Result<Regex, MapReduceError> {
+        let regex = RegexBuilder::new(pattern)
+            .case_insensitive(self.case_insensitive)
+            .multi_line(self.multi_line)
+            .build()?;
+        Ok(regex)
+    }
+}
+
+#[derive(Clone)]
+pub struct RegexMatchCountWorker {
+    input_data: Arc<dyn GenericInputData<String>>,
+    pattern: Regex,
+    result: usize,
+}
+
+impl RegexMatchCountWorker {
+    pub fn new(input_data: Arc<dyn GenericInputData<String>>, pattern: Regex) -> Self {
+        Self {
+            input_data,
+            pattern,
+            result: 0,
+        }
+    }
+
+    pub fn with_options(
+        input_data: Arc<dyn GenericInputData<String>>,
+        pattern: &str,
+        options: RegexOptions,
+    ) -> Result<Self, MapReduceError> {
+        Ok(Self::new(input_data, options.build(pattern)?))
+    }
+}
+
+impl Mapper for RegexMatchCountWorker {
+    fn map(&mut self) -> Result<(), MapReduceError> {
+        let data = self.input_data.read()?;
+        self.result = data
+            .lines()
+            .filter(|line| self.pattern.is_match(line))
+            .count();
+        Ok(())
+    }
+
+    fn input_path(&self) -> Option<&Path> {
+        self.input_data.path()
+    }
+}
+
+impl Reducer for RegexMatchCountWorker {
+    type Output = usize;
+
+    fn reduce(&mut self, other: &Self) {
+        self.result += other.result;
+    }
+
+    fn get_result(&self) -> Self::Output {
+        self.result
+    }
+}
+
+impl Combine for RegexMatchCountWorker {
+    fn identity() -> Self {
+        Self::new(
+            Arc::new(StringInputData::default()),
+            Regex::new("").expect("Empty pattern is always valid"),
+        )
+    }
+}
+
+impl MapReducer for RegexMatchCountWorker {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{create_workers, generate_inputs, run_map_reduce, test_util::TempDir, RunOptions};
+
+    #[test]
+    fn counts_lines_matching_warn() {
+        let dir = TempDir::new();
+        dir.write("a.log", "WARN disk\nINFO ok\nWARN cpu\n");
+        dir.write("b.log", "ERROR WARN later\nWARN memory\n");
+
+        let pattern = Regex::new("^WARN").unwrap();
+        let workers = create_workers(generate_inputs(dir.path()).unwrap(), |input| {
+            RegexMatchCountWorker::new(input, pattern.clone())
+        });
+        assert_eq!(
+            run_map_reduce(workers, RunOptions::new()).unwrap().result,
+            3
+        );
+    }
+
+    #[test]
+    fn case_insensitive_patterns_match_other_cases() {
+        let options = RegexOptions {
+            case_insensitive: true,
+            ..RegexOptions::default()
+        };
+        let mut worker = RegexMatchCountWorker::with_options(
+            Arc::new(StringInputData::new(
+                "ERROR disk
+error cpu
+ok",
+            )),
+            "error",
+            options,
+        )
+        .unwrap();
+        worker.map().unwrap();
+        assert_eq!(worker.get_result(), 2);
+    }
+
+    #[test]
+    fn invalid_patterns_are_rejected() {
+        let worker = RegexMatchCountWorker::with_options(
+            Arc::new(StringInputData::default()),
+            "(unclosed",
+            RegexOptions::default(),
+        );
+        assert!(matches!(worker, Err(MapReduceError::InvalidPattern(_))));
+    }
+}
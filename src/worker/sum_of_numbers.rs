@@ -0,0 +1,95 @@
+use std::{path::Path, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Combine, GenericInputData, MapReduceError, MapReducer, Mapper, Reducer, StringInputData,
+    WorkerOutput,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct NumberSum {
+    pub sum: f64,
+    /// Non-blank lines that were skipped because they are not numbers.
+    pub parse_errors: usize,
+}
+
+impl From<NumberSum> for WorkerOutput {
+    fn from(result: NumberSum) -> Self {
+        Self::Float(result.sum)
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SumOfNumbersWorker {
+    #[serde(skip, default = "super::no_input")]
+    input_data: Arc<dyn GenericInputData<String>>,
+    result: NumberSum,
+}
+
+impl SumOfNumbersWorker {
+    pub fn new(input_data: Arc<dyn GenericInputData<String>>) -> Self {
+        Self {
+            input_data,
+            result: NumberSum::default(),
+        }
+    }
+}
+
+impl Mapper for SumOfNumbersWorker {
+    fn map(&mut self) -> Result<(), MapReduceError> {
+        let data = self.input_data.read()?;
+        self.result = NumberSum::default();
+        for line in data.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            match line.parse::<f64>() {
+                Ok(number) => self.result.sum += number,
+                Err(_) => self.result.parse_errors += 1,
+            }
+        }
+        Ok(())
+    }
+
+    fn input_path(&self) -> Option<&Path> {
+        self.input_data.path()
+    }
+}
+
+impl Reducer for SumOfNumbersWorker {
+    type Output = NumberSum;
+
+    fn reduce(&mut self, other: &Self) {
+        self.result.sum += other.result.sum;
+        self.result.parse_errors += other.result.parse_errors;
+    }
+
+    fn get_result(&self) -> Self::Output {
+        self.result
+    }
+}
+
+impl Combine for SumOfNumbersWorker {
+    fn identity() -> Self {
+        Self::new(Arc::new(StringInputData::default()))
+    }
+}
+
+impl MapReducer for SumOfNumbersWorker {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{run, test_util::string_inputs};
+
+    #[test]
+    fn sums_numbers_and_counts_junk_lines() {
+        let inputs = string_inputs(&["1.5\nabc\n  2 \n\n", "-0.5\n1e1\nNaN-ish\n"]);
+        let result = run(inputs, SumOfNumbersWorker::new).unwrap();
+        assert_eq!(
+            result,
+            NumberSum {
+                sum: 13.0,
+                parse_errors: 2
+            }
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::Path,
+    sync::Arc,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    top_words, Combine, GenericInputData, MapReduceError, MapReducer, Mapper, Reducer,
+    StringInputData, TextNormalizer,
+};
+
+const COUNTERS_PER_RESULT: usize = 100;
+
+// Space-Saving: at most `capacity` words are counted at a time, and a new word
+// takes over the counter of the least frequent one, inheriting its count.
+// Counts are therefore upper bounds, overestimated by at most the number of
+// words seen divided by `capacity`, which is why many more words are tracked
+// than are reported.
+#[derive(Clone, Default, Serialize, Deserialize)]
+pub(crate) struct SpaceSaving {
+    pub(crate) capacity: usize,
+    counters: HashMap<String, usize>,
+    by_count: BTreeSet<(usize, String)>,
+}
+
+impl SpaceSaving {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            ..Self::default()
+        }
+    }
+
+    /// Starts from exact `counts`, keeping only the `capacity` largest.
+    pub(crate) fn from_counts(counts: &HashMap<String, usize>, capacity: usize) -> Self {
+        let mut space_saving = Self::new(capacity);
+        space_saving.merge(counts);
+        space_saving
+    }
+
+    pub(crate) fn counts(&self) -> &HashMap<String, usize> {
+        &self.counters
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.counters.clear();
+        self.by_count.clear();
+    }
+
+    pub(crate) fn insert(&mut self, word: String) {
+        let count = match self.counters.get(&word) {
+            Some(&count) => {
+                self.by_count.remove(&(count, word.clone()));
+                count
+            }
+            None if self.counters.len() < self.capacity => 0,
+            None => match self.by_count.pop_first() {
+                Some((count, evicted)) => {
+                    self.counters.remove(&evicted);
+                    count
+                }
+                None => return,
+            },
+        };
+        self.by_count.insert((count + 1, word.clone()));
+        self.counters.insert(word, count + 1);
+    }
+
+    pub(crate) fn merge(&mut self, counts: &HashMap<String, usize>) {
+        for (word, &count) in counts {
+            *self.counters.entry(word.clone()).or_insert(0) += count;
+        }
+        self.counters = top_words(&self.counters, self.capacity)
+            .into_iter()
+            .collect();
+        self.by_count = self
+            .counters
+            .iter()
+            .map(|(word, &count)| (count, word.clone()))
+            .collect();
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TopKWordWorker {
+    #[serde(skip, default = "super::no_input")]
+    input_data: Arc<dyn GenericInputData<String>>,
+    k: usize,
+    counters: SpaceSaving,
+}
+
+impl TopKWordWorker {
+    pub fn new(input_data: Arc<dyn GenericInputData<String>>, k: usize) -> Self {
+        Self::with_capacity(input_data, k, k.saturating_mul(COUNTERS_PER_RESULT))
+    }
+
+    pub fn with_capacity(
+        input_data: Arc<dyn GenericInputData<String>>,
+        k: usize,
+        capacity: usize,
+    ) -> Self {
+        Self {
+            input_data,
+            k,
+            counters: SpaceSaving::new(capacity.max(k)),
+        }
+    }
+}
+
+impl Mapper for TopKWordWorker {
+    fn map(&mut self) -> Result<(), MapReduceError> {
+        let data = self.input_data.read()?;
+        self.counters.clear();
+        for word in data
+            .split_whitespace()
+            .filter_map(|word| TextNormalizer::LOWERCASE.apply(word))
+        {
+            self.counters.insert(word);
+        }
+        Ok(())
+    }
+
+    fn input_path(&self) -> Option<&Path> {
+        self.input_data.path()
+    }
+}
+
+impl Reducer for TopKWordWorker {
+    type Output = Vec<(String, usize)>;
+
+    fn reduce(&mut self, other: &Self) {
+        // The identity has no size of its own, so it takes on the other's.
+        self.k = self.k.max(other.k);
+        self.counters.capacity = self.counters.capacity.max(other.counters.capacity);
+        self.counters.merge(other.counters.counts());
+    }
+
+    fn get_result(&self) -> Self::Output {
+        top_words(self.counters.counts(), self.k)
+    }
+}
+
+impl Combine for TopKWordWorker {
+    fn identity() -> Self {
+        Self::new(Arc::new(StringInputData::default()), 0)
+    }
+}
+
+impl MapReducer for TopKWordWorker {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run;
+
+    // 500 words seen once each, interleaved with three words seen hundreds
+    // of times.
+    fn skewed(offset: usize) -> Box<dyn GenericInputData<String>> {
+        let mut words = Vec::new();
+        for index in 0..500 {
+            words.push(format!("rare{}", offset + index));
+            for (word, count) in [("alpha", 250), ("beta", 150), ("gamma", 100)] {
+                if index < count {
+                    words.push(word.to_string());
+                }
+            }
+        }
+        Box::new(StringInputData::new(words.join(" ")))
+    }
+
+    #[test]
+    fn heavy_hitters_survive_bounded_counters() {
+        let top = run(vec![skewed(0), skewed(500)], |input| {
+            TopKWordWorker::with_capacity(input, 3, 30)
+        })
+        .unwrap();
+        let words: Vec<&str> = top.iter().map(|(word, _)| word.as_str()).collect();
+        assert_eq!(words, ["alpha", "beta", "gamma"]);
+    }
+}
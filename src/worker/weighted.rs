@@ -0,0 +1,103 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{Combine, MapReduceError, MapReducer, Mapper, Reducer};
+
+/// Wraps a worker so that it counts as `weight` copies of itself when
+/// reduced, e.g. for a sampled file that stands in for several others.
+#[derive(Serialize, Deserialize)]
+pub struct WeightedWorker<W> {
+    worker: W,
+    weight: usize,
+}
+
+impl<W: MapReducer> WeightedWorker<W> {
+    pub fn new(worker: W, weight: usize) -> Self {
+        Self { worker, weight }
+    }
+
+    pub fn weight(&self) -> usize {
+        self.weight
+    }
+
+    // Reducing the worker into an identity `weight` times works for any
+    // output, not just the ones that can be multiplied.
+    fn scaled(&self) -> W {
+        let mut scaled = W::identity();
+        for _ in 0..self.weight {
+            scaled.reduce(&self.worker);
+        }
+        scaled
+    }
+}
+
+impl<W: MapReducer> Mapper for WeightedWorker<W> {
+    fn map(&mut self) -> Result<(), MapReduceError> {
+        self.worker.map()
+    }
+
+    fn input_path(&self) -> Option<&Path> {
+        self.worker.input_path()
+    }
+}
+
+impl<W: MapReducer> Reducer for WeightedWorker<W> {
+    type Output = W::Output;
+
+    fn reduce(&mut self, other: &Self) {
+        // The accumulated worker keeps its own weight only until it absorbs
+        // others; from then on it holds the weighted total.
+        if self.weight != 1 {
+            self.worker = self.scaled();
+            self.weight = 1;
+        }
+        for _ in 0..other.weight {
+            self.worker.reduce(&other.worker);
+        }
+    }
+
+    fn get_result(&self) -> Self::Output {
+        if self.weight == 1 {
+            self.worker.get_result()
+        } else {
+            self.scaled().get_result()
+        }
+    }
+}
+
+impl<W: MapReducer> Combine for WeightedWorker<W> {
+    fn identity() -> Self {
+        Self::new(W::identity(), 1)
+    }
+}
+
+impl<W: MapReducer> MapReducer for WeightedWorker<W> {}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use parking_lot::Mutex;
+
+    use super::*;
+    use crate::{aggregate, LineCountWorker, StringInputData};
+
+    fn weighted(content: &str, weight: usize) -> Arc<Mutex<WeightedWorker<LineCountWorker>>> {
+        let worker = LineCountWorker::new(Arc::new(StringInputData::new(content)));
+        Arc::new(Mutex::new(WeightedWorker::new(worker, weight)))
+    }
+
+    #[test]
+    fn a_weight_of_three_triples_the_contribution() {
+        assert_eq!(
+            aggregate(vec![weighted("a\nb", 1), weighted("c", 1)]).unwrap(),
+            3
+        );
+        assert_eq!(
+            aggregate(vec![weighted("a\nb", 3), weighted("c", 1)]).unwrap(),
+            7
+        );
+        assert_eq!(aggregate(vec![weighted("a\nb", 3)]).unwrap(), 6);
+    }
+}
@@ -0,0 +1,87 @@
+use std::{path::Path, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Combine, GenericInputData, MapReduceError, MapReducer, Mapper, Reducer, StringInputData,
+    TextNormalizer,
+};
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct WordCountWorker {
+    #[serde(skip, default = "super::no_input")]
+    input_data: Arc<dyn GenericInputData<String>>,
+    normalizer: TextNormalizer,
+    result: usize,
+}
+
+impl WordCountWorker {
+    pub fn new(input_data: Arc<dyn GenericInputData<String>>) -> Self {
+        Self::with_normalizer(input_data, TextNormalizer::NONE)
+    }
+
+    pub fn with_normalizer(
+        input_data: Arc<dyn GenericInputData<String>>,
+        normalizer: TextNormalizer,
+    ) -> Self {
+        Self {
+            input_data,
+            normalizer,
+            result: 0,
+        }
+    }
+}
+
+impl Mapper for WordCountWorker {
+    fn map(&mut self) -> Result<(), MapReduceError> {
+        let data = self.input_data.read()?;
+        self.result = data
+            .split_whitespace()
+            .filter_map(|word| self.normalizer.apply(word))
+            .count();
+        Ok(())
+    }
+
+    fn input_path(&self) -> Option<&Path> {
+        self.input_data.path()
+    }
+}
+
+impl Reducer for WordCountWorker {
+    type Output = usize;
+
+    fn reduce(&mut self, other: &Self) {
+        self.result += other.result;
+    }
+
+    fn get_result(&self) -> Self::Output {
+        self.result
+    }
+}
+
+impl Combine for WordCountWorker {
+    fn identity() -> Self {
+        Self::new(Arc::new(StringInputData::default()))
+    }
+}
+
+impl MapReducer for WordCountWorker {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{create_workers, generate_inputs, run_map_reduce, test_util::TempDir, RunOptions};
+
+    #[test]
+    fn counts_words_across_a_directory() {
+        let dir = TempDir::new();
+        dir.write("a.txt", "the quick  brown\tfox\n");
+        dir.write("b.txt", "jumps over\n\nthe lazy dog");
+        dir.write("empty.txt", "");
+
+        let workers = create_workers(generate_inputs(dir.path()).unwrap(), WordCountWorker::new);
+        let summary = run_map_reduce(workers, RunOptions::new()).unwrap();
+        assert_eq!(summary.result, 9);
+        assert_eq!(summary.inputs_processed, 3);
+    }
+}
@@ -0,0 +1,353 @@
+use std::{
+    collections::{HashMap, HashSet},
+    mem,
+    path::Path,
+    sync::{Arc, OnceLock},
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::top_k_word::SpaceSaving;
+use crate::{
+    Combine, GenericInputData, MapReduceError, MapReducer, Mapper, Reducer, StringInputData,
+    TextNormalizer, WorkerOutput,
+};
+
+const DEFAULT_STOP_WORDS: &[&str] = &[
+    "a", "about", "after", "all", "also", "an", "and", "any", "are", "as", "at", "be", "been",
+    "but", "by", "can", "could", "did", "do", "does", "for", "from", "had", "has", "have", "he",
+    "her", "his", "how", "i", "if", "in", "into", "is", "it", "its", "just", "me", "more", "my",
+    "no", "not", "of", "on", "one", "or", "our", "out", "she", "so", "some", "than", "that", "the",
+    "their", "them", "then", "there", "these", "they", "this", "to", "up", "us", "was", "we",
+    "were", "what", "when", "which", "who", "will", "with", "would", "you", "your",
+];
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WordCounts {
+    pub counts: HashMap<String, usize>,
+    /// Set once the words outgrew the memory limit; `counts` then only holds
+    /// the most frequent words, with counts that may be overestimated.
+    pub approximate: bool,
+}
+
+impl From<WordCounts> for WorkerOutput {
+    fn from(words: WordCounts) -> Self {
+        Self::Histogram {
+            counts: words.counts,
+            approximate: words.approximate,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+enum Counts {
+    Exact {
+        counts: HashMap<String, usize>,
+        bytes: usize,
+    },
+    Approximate(SpaceSaving),
+}
+
+impl Default for Counts {
+    fn default() -> Self {
+        Self::Exact {
+            counts: HashMap::new(),
+            bytes: 0,
+        }
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct WordFrequencyWorker {
+    #[serde(skip, default = "super::no_input")]
+    input_data: Arc<dyn GenericInputData<String>>,
+    normalizer: TextNormalizer,
+    #[serde(skip)]
+    stop_words: Arc<HashSet<String>>,
+    max_memory_bytes: Option<usize>,
+    counts: Counts,
+}
+
+impl WordFrequencyWorker {
+    pub fn new(input_data: Arc<dyn GenericInputData<String>>) -> Self {
+        Self::with_normalizer(input_data, TextNormalizer::LOWERCASE)
+    }
+
+    pub fn with_normalizer(
+        input_data: Arc<dyn GenericInputData<String>>,
+        normalizer: TextNormalizer,
+    ) -> Self {
+        Self {
+            input_data,
+            normalizer,
+            stop_words: Arc::default(),
+            max_memory_bytes: None,
+            counts: Counts::default(),
+        }
+    }
+
+    /// Past `max_memory_bytes`, only as many words as fit at that point are
+    /// tracked from then on, Space-Saving style. The limit is checked against
+    /// an estimate of the memory the counts take up.
+    pub fn with_max_memory(
+        input_data: Arc<dyn GenericInputData<String>>,
+        max_memory_bytes: usize,
+    ) -> Self {
+        Self {
+            max_memory_bytes: Some(max_memory_bytes),
+            ..Self::new(input_data)
+        }
+    }
+
+    /// Stop words are matched after normalizing, so with the default
+    /// lowercasing normalizer they should be given in lowercase.
+    pub fn with_stop_words(
+        input_data: Arc<dyn GenericInputData<String>>,
+        stop_words: impl Into<Arc<HashSet<String>>>,
+    ) -> Self {
+        Self {
+            stop_words: stop_words.into(),
+            ..Self::new(input_data)
+        }
+    }
+
+    pub fn with_default_stopwords(input_data: Arc<dyn GenericInputData<String>>) -> Self {
+        static STOP_WORDS: OnceLock<Arc<HashSet<String>>> = OnceLock::new();
+        let stop_words = STOP_WORDS.get_or_init(|| {
+            Arc::new(
+                DEFAULT_STOP_WORDS
+                    .iter()
+                    .map(|&word| word.to_owned())
+                    .collect(),
+            )
+        });
+        Self::with_stop_words(input_data, Arc::clone(stop_words))
+    }
+
+    pub fn top_n(&self, n: usize) -> Vec<(String, usize)> {
+        top_words(self.current_counts(), n)
+    }
+
+    fn current_counts(&self) -> &HashMap<String, usize> {
+        match &self.counts {
+            Counts::Exact { counts, .. } => counts,
+            Counts::Approximate(space_saving) => space_saving.counts(),
+        }
+    }
+
+    fn insert(&mut self, word: String) {
+        match &mut self.counts {
+            Counts::Exact { counts, bytes } => {
+                if !counts.contains_key(&word) {
+                    *bytes += entry_size(&word);
+                }
+                *counts.entry(word).or_insert(0) += 1;
+                self.check_memory();
+            }
+            Counts::Approximate(space_saving) => space_saving.insert(word),
+        }
+    }
+
+    fn add_counts(&mut self, other: &HashMap<String, usize>) {
+        match &mut self.counts {
+            Counts::Exact { counts, bytes } => {
+                for (word, &count) in other {
+                    if !counts.contains_key(word) {
+                        *bytes += entry_size(word);
+                    }
+                    *counts.entry(word.clone()).or_insert(0) += count;
+                }
+                self.check_memory();
+            }
+            Counts::Approximate(space_saving) => space_saving.merge(other),
+        }
+    }
+
+    fn check_memory(&mut self) {
+        if let Counts::Exact { counts, bytes } = &self.counts {
+            if self.max_memory_bytes.is_some_and(|max| *bytes > max) {
+                log::warn!("word counts exceeded {bytes} bytes, switching to an estimate");
+                self.make_approximate(counts.len());
+            }
+        }
+    }
+
+    fn make_approximate(&mut self, capacity: usize) {
+        if let Counts::Exact { counts, .. } = &self.counts {
+            self.counts = Counts::Approximate(SpaceSaving::from_counts(counts, capacity));
+        }
+    }
+}
+
+fn entry_size(word: &str) -> usize {
+    mem::size_of::<String>() + word.len() + mem::size_of::<usize>()
+}
+
+pub fn top_words(counts: &HashMap<String, usize>, n: usize) -> Vec<(String, usize)> {
+    let mut words: Vec<(String, usize)> = counts
+        .iter()
+        .map(|(word, &count)| (word.clone(), count))
+        .collect();
+    // Ties are broken alphabetically so the output doesn't depend on hash order.
+    words.sort_by(|(a_word, a_count), (b_word, b_count)| {
+        b_count.cmp(a_count).then_with(|| a_word.cmp(b_word))
+    });
+    words.truncate(n);
+    words
+}
+
+impl Mapper for WordFrequencyWorker {
+    fn map(&mut self) -> Result<(), MapReduceError> {
+        let data = self.input_data.read()?;
+        self.counts = Counts::default();
+        for word in data.split_whitespace() {
+            if let Some(word) = self.normalizer.apply(word) {
+                if !self.stop_words.contains(&word) {
+                    self.insert(word);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn input_path(&self) -> Option<&Path> {
+        self.input_data.path()
+    }
+}
+
+impl Reducer for WordFrequencyWorker {
+    type Output = WordCounts;
+
+    fn reduce(&mut self, other: &Self) {
+        // The identity has no limit of its own, so it takes on the other's.
+        self.max_memory_bytes = self.max_memory_bytes.or(other.max_memory_bytes);
+        match &other.counts {
+            Counts::Exact { counts, .. } => self.add_counts(counts),
+            Counts::Approximate(other_counts) => {
+                self.make_approximate(other_counts.capacity);
+                if let Counts::Approximate(space_saving) = &mut self.counts {
+                    space_saving.capacity = space_saving.capacity.max(other_counts.capacity);
+                    space_saving.merge(other_counts.counts());
+                }
+            }
+        }
+    }
+
+    fn get_result(&self) -> Self::Output {
+        WordCounts {
+            counts: self.current_counts().clone(),
+            approximate: matches!(self.counts, Counts::Approximate(_)),
+        }
+    }
+}
+
+impl Combine for WordFrequencyWorker {
+    fn identity() -> Self {
+        Self::new(Arc::new(StringInputData::default()))
+    }
+}
+
+impl MapReducer for WordFrequencyWorker {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{run, test_util::string_inputs};
+
+    #[test]
+    fn merges_lowercased_counts_across_inputs() {
+        let inputs = string_inputs(&["The cat saw the dog", "the DOG ran"]);
+        let words = run(inputs, WordFrequencyWorker::new).unwrap();
+        assert!(!words.approximate);
+        assert_eq!(words.counts.len(), 5);
+        assert_eq!(
+            top_words(&words.counts, 3),
+            [
+                ("the".to_string(), 3),
+                ("dog".to_string(), 2),
+                ("cat".to_string(), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn normalized_tokens_collapse_to_one_key() {
+        let normalizer = TextNormalizer::LOWERCASE | TextNormalizer::STRIP_PUNCTUATION;
+        let words = run(string_inputs(&["Hello, hello", "HELLO! --"]), |input| {
+            WordFrequencyWorker::with_normalizer(input, normalizer)
+        })
+        .unwrap();
+        assert_eq!(words.counts, HashMap::from([("hello".to_string(), 3)]));
+
+        let raw = run(string_inputs(&["Hello, hello"]), |input| {
+            WordFrequencyWorker::with_normalizer(input, TextNormalizer::NONE)
+        })
+        .unwrap();
+        assert_eq!(raw.counts.len(), 2);
+    }
+
+    #[test]
+    fn top_n_breaks_ties_alphabetically() {
+        let mut worker = WordFrequencyWorker::new(Arc::new(StringInputData::new(
+            "pear apple fig apple pear kiwi apple fig",
+        )));
+        worker.map().unwrap();
+        assert_eq!(
+            worker.top_n(3),
+            [
+                ("apple".to_string(), 3),
+                ("fig".to_string(), 2),
+                ("pear".to_string(), 2)
+            ]
+        );
+        assert_eq!(worker.top_n(10).len(), 4);
+    }
+
+    #[test]
+    fn stop_words_are_left_out_of_the_histogram() {
+        let inputs = string_inputs(&["The cat and the dog", "a dog AND a bird"]);
+        let words = run(inputs, WordFrequencyWorker::with_default_stopwords).unwrap();
+        assert_eq!(
+            words.counts,
+            HashMap::from([
+                ("cat".to_string(), 1),
+                ("dog".to_string(), 2),
+                ("bird".to_string(), 1)
+            ])
+        );
+
+        let custom = HashSet::from(["dog".to_string()]);
+        let words = run(string_inputs(&["dog cat dog"]), |input| {
+            WordFrequencyWorker::with_stop_words(input, custom.clone())
+        })
+        .unwrap();
+        assert_eq!(words.counts, HashMap::from([("cat".to_string(), 1)]));
+    }
+
+    #[test]
+    fn a_tiny_memory_limit_keeps_the_most_frequent_words() {
+        let rare: String = (0..2_000).map(|word| format!("rare{word} ")).collect();
+        let common = "common ".repeat(500);
+        let inputs = string_inputs(&[&common, &rare, &common]);
+
+        let words = run(inputs, |input| {
+            WordFrequencyWorker::with_max_memory(input, 1024)
+        })
+        .unwrap();
+        assert!(words.approximate);
+        assert!(words.counts.len() < 100);
+        // Space-Saving can overestimate a count but never underestimates one.
+        let common_count = words.counts["common"];
+        assert!(
+            (1_000..1_100).contains(&common_count),
+            "counted {common_count} common words"
+        );
+        assert!(matches!(
+            WorkerOutput::from(words),
+            WorkerOutput::Histogram {
+                approximate: true,
+                ..
+            }
+        ));
+    }
+}